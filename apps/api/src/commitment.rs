@@ -0,0 +1,132 @@
+use axum::{extract::rejection::JsonRejection, http::StatusCode, Json};
+use notary::commitment::{id_commitment, verify_id_commitment};
+use notary::{Nuip, Salt};
+use serde::{Deserialize, Serialize};
+
+use crate::proof::{reject_body, ErrorResponse};
+
+/// Request body for identity commitment computation
+#[derive(Debug, Deserialize)]
+pub struct CommitmentRequest {
+    /// National ID number (NUIP), at most 15 characters
+    pub nuip: Nuip,
+    /// Salt for identity commitment (64 hex characters, 0x optional)
+    pub salt: Salt,
+    /// Registered commitment to check against (hex string, with or without 0x prefix)
+    #[serde(default)]
+    pub commitment: Option<String>,
+}
+
+/// Response body for identity commitment computation
+#[derive(Debug, Serialize)]
+pub struct CommitmentResponse {
+    /// `SHA256(nuip zero-padded to 15 bytes || salt)`, 0x-prefixed - the `registerIdentity` argument
+    pub id_commitment: String,
+    /// Whether the request's `commitment` matches, when one was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matches: Option<bool>,
+}
+
+fn parse_commitment(value: &str) -> Result<[u8; 32], (StatusCode, Json<ErrorResponse>)> {
+    let value = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+
+    hex::decode(value)
+        .map_err(|e| format!("Invalid commitment: {}", e))
+        .and_then(|bytes| {
+            bytes.try_into().map_err(|v: Vec<u8>| {
+                format!("Commitment must be exactly 32 bytes, got {}", v.len())
+            })
+        })
+        .map_err(|error| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::invalid_input(error)),
+            )
+        })
+}
+
+/// Compute the identity commitment a testator registers with `registerIdentity`
+///
+/// Uses the same padding as the circuit, so the registered value matches the proof's `id_commitment`.
+/// With `commitment` set, also reports whether it matches.
+pub async fn compute_commitment(
+    payload: Result<Json<CommitmentRequest>, JsonRejection>,
+) -> Result<Json<CommitmentResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(request) = payload.map_err(reject_body)?;
+
+    let matches = match &request.commitment {
+        Some(expected) => Some(verify_id_commitment(
+            &request.nuip,
+            request.salt,
+            &parse_commitment(expected)?,
+        )),
+        None => None,
+    };
+
+    Ok(Json(CommitmentResponse {
+        id_commitment: format!(
+            "0x{}",
+            hex::encode(id_commitment(&request.nuip, request.salt))
+        ),
+        matches,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notary::commitment::TEST_VECTORS;
+
+    fn request(
+        nuip: &str,
+        salt: &str,
+        commitment: Option<&str>,
+    ) -> Result<Json<CommitmentRequest>, JsonRejection> {
+        let body = serde_json::json!({ "nuip": nuip, "salt": salt, "commitment": commitment });
+        Json::from_bytes(body.to_string().as_bytes())
+    }
+
+    #[tokio::test]
+    async fn test_compute_commitment_matches_test_vectors() {
+        for vector in TEST_VECTORS {
+            let Json(response) = compute_commitment(request(vector.nuip, vector.salt, None))
+                .await
+                .unwrap();
+            assert_eq!(
+                response.id_commitment,
+                format!("0x{}", vector.id_commitment)
+            );
+            assert_eq!(response.matches, None);
+
+            let Json(response) = compute_commitment(request(
+                vector.nuip,
+                &format!("0x{}", vector.salt),
+                Some(vector.id_commitment),
+            ))
+            .await
+            .unwrap();
+            assert_eq!(response.matches, Some(true));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compute_commitment_rejects_invalid_input() {
+        let salt = TEST_VECTORS[0].salt;
+
+        for (nuip, salt, commitment) in [
+            ("", salt, None),
+            ("1234567890123456", salt, None),
+            ("454545454", "1111", None),
+            ("454545454", salt, Some("abcd")),
+        ] {
+            let (status, Json(error)) = compute_commitment(request(nuip, salt, commitment))
+                .await
+                .unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(error.code, "INVALID_INPUT");
+        }
+    }
+}
@@ -0,0 +1,762 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures::stream::{self, Stream, StreamExt};
+use notary::{Progress, ProgressEvent, ProofStage};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::AbortHandle;
+
+use crate::proof::{ErrorResponse, ProofResponse};
+
+/// Finished jobs are kept this long by [`InMemoryJobStore`] so clients can still poll the result
+pub const JOB_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Default for `PROOF_JOB_LIMIT`: jobs queued or running at once before new ones are refused
+pub const DEFAULT_JOB_LIMIT: usize = 8;
+
+/// `Retry-After` sent with `503 QUEUE_FULL`
+pub const RETRY_AFTER_SECS: u64 = 30;
+
+/// Events buffered per job for slow `GET /api/jobs/{id}/events` subscribers
+const EVENT_BUFFER: usize = 64;
+
+/// Lifecycle of a proof job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStage {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStage {
+    /// Whether the job can no longer change
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStage::Succeeded | JobStage::Failed | JobStage::Cancelled
+        )
+    }
+}
+
+/// Failure of a proof job: the error and the HTTP status a synchronous call would have returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobError {
+    pub status: u16,
+    #[serde(flatten)]
+    pub error: ErrorResponse,
+}
+
+/// A proof generation stage the job went through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageRecord {
+    pub stage: ProofStage,
+    /// Unix timestamp (milliseconds)
+    pub started_at_ms: u64,
+    /// Set once the next stage started or the proof run finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+/// A proof generation job, as returned by `GET /api/jobs/{id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub stage: JobStage,
+    /// Unix timestamps (seconds)
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// Proof generation stages reached so far, in order
+    #[serde(default)]
+    pub progress: Vec<StageRecord>,
+    /// Proof and submission outcome, once `succeeded`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<ProofResponse>,
+    /// Why the job failed, once `failed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JobError>,
+}
+
+/// State change reported for a job
+#[derive(Debug, Clone)]
+pub enum JobUpdate {
+    Running,
+    /// A proof generation stage started or completed; the job stays `running`
+    Progress(ProgressEvent),
+    Succeeded(ProofResponse),
+    Failed(JobError),
+    Cancelled,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn unix_millis(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl Job {
+    fn new(id: String) -> Self {
+        let now = now();
+        Job {
+            id,
+            stage: JobStage::Queued,
+            created_at: now,
+            updated_at: now,
+            progress: Vec::new(),
+            result: None,
+            error: None,
+        }
+    }
+
+    /// Apply `update` unless the job already finished; returns whether it changed
+    ///
+    /// Store backends call this so a job cancelled while finishing keeps a single outcome.
+    pub fn apply(&mut self, update: JobUpdate) -> bool {
+        if self.stage.is_finished() {
+            return false;
+        }
+
+        self.stage = match update {
+            JobUpdate::Running => JobStage::Running,
+            JobUpdate::Progress(event) => {
+                self.record_progress(event);
+                self.stage
+            }
+            JobUpdate::Succeeded(result) => {
+                self.result = Some(result);
+                JobStage::Succeeded
+            }
+            JobUpdate::Failed(error) => {
+                self.error = Some(error);
+                JobStage::Failed
+            }
+            JobUpdate::Cancelled => JobStage::Cancelled,
+        };
+        self.updated_at = now();
+        true
+    }
+
+    fn record_progress(&mut self, event: ProgressEvent) {
+        match event {
+            ProgressEvent::Started { stage, at } => self.progress.push(StageRecord {
+                stage,
+                started_at_ms: unix_millis(at),
+                duration_ms: None,
+            }),
+            ProgressEvent::Completed {
+                stage, duration, ..
+            } => {
+                if let Some(record) = self.progress.iter_mut().rev().find(|r| r.stage == stage) {
+                    record.duration_ms = Some(duration.as_millis() as u64);
+                }
+            }
+        }
+    }
+}
+
+/// `started` or `completed`, in a [`StageEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Started,
+    Completed,
+}
+
+/// `stage` event of `GET /api/jobs/{id}/events`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageEvent {
+    pub stage: ProofStage,
+    pub status: StageStatus,
+    /// Unix timestamp (milliseconds)
+    pub at_ms: u64,
+    /// How long the stage took, once `completed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+impl From<ProgressEvent> for StageEvent {
+    fn from(event: ProgressEvent) -> Self {
+        match event {
+            ProgressEvent::Started { stage, at } => StageEvent {
+                stage,
+                status: StageStatus::Started,
+                at_ms: unix_millis(at),
+                duration_ms: None,
+            },
+            ProgressEvent::Completed {
+                stage,
+                at,
+                duration,
+            } => StageEvent {
+                stage,
+                status: StageStatus::Completed,
+                at_ms: unix_millis(at),
+                duration_ms: Some(duration.as_millis() as u64),
+            },
+        }
+    }
+}
+
+/// Published to subscribers of a running job
+#[derive(Debug, Clone)]
+pub enum JobEvent {
+    Stage(StageEvent),
+    /// The job as stored: first event of a stream, and last once the job finished
+    Job(Job),
+}
+
+impl JobEvent {
+    fn into_sse(self) -> Result<Event, axum::Error> {
+        match self {
+            JobEvent::Stage(stage) => Event::default().event("stage").json_data(stage),
+            JobEvent::Job(job) => Event::default().event("job").json_data(job),
+        }
+    }
+}
+
+/// Failure of the job store backend
+#[derive(Debug, thiserror::Error)]
+#[error("job store failure: {0}")]
+pub struct JobStoreError(pub String);
+
+/// Where job state lives
+///
+/// [`InMemoryJobStore`] keeps it in the API process; a persistent backend implements the same
+/// trait. Implementations must apply updates atomically with [`Job::apply`].
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    async fn insert(&self, job: Job) -> Result<(), JobStoreError>;
+
+    async fn get(&self, id: &str) -> Result<Option<Job>, JobStoreError>;
+
+    /// Apply `update` to the job; returns the job as stored afterwards, `None` if unknown
+    async fn update(&self, id: &str, update: JobUpdate) -> Result<Option<Job>, JobStoreError>;
+}
+
+/// Jobs held in memory; finished jobs are dropped after [`JOB_RETENTION`]
+#[derive(Default)]
+pub struct InMemoryJobStore {
+    jobs: RwLock<HashMap<String, Job>>,
+}
+
+#[async_trait]
+impl JobStore for InMemoryJobStore {
+    async fn insert(&self, job: Job) -> Result<(), JobStoreError> {
+        let mut jobs = self.jobs.write().unwrap();
+        let cutoff = now().saturating_sub(JOB_RETENTION.as_secs());
+        jobs.retain(|_, job| !job.stage.is_finished() || job.updated_at >= cutoff);
+        jobs.insert(job.id.clone(), job);
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Job>, JobStoreError> {
+        Ok(self.jobs.read().unwrap().get(id).cloned())
+    }
+
+    async fn update(&self, id: &str, update: JobUpdate) -> Result<Option<Job>, JobStoreError> {
+        let mut jobs = self.jobs.write().unwrap();
+        Ok(jobs.get_mut(id).map(|job| {
+            job.apply(update);
+            job.clone()
+        }))
+    }
+}
+
+/// Why [`Jobs::spawn`] did not queue a job
+#[derive(Debug, thiserror::Error)]
+pub enum SpawnError {
+    #[error("{limit} proof jobs are already queued or running")]
+    Full { limit: usize },
+    #[error(transparent)]
+    Store(#[from] JobStoreError),
+}
+
+/// Task and subscribers of an unfinished job
+struct RunningJob {
+    task: AbortHandle,
+    events: broadcast::Sender<JobEvent>,
+}
+
+/// Runs proof jobs in the background and tracks them in a [`JobStore`]
+#[derive(Clone)]
+pub struct Jobs {
+    store: Arc<dyn JobStore>,
+    /// Unfinished jobs, to abort on cancel and to stream events from
+    running: Arc<Mutex<HashMap<String, RunningJob>>>,
+    /// Most unfinished jobs at once
+    limit: usize,
+}
+
+impl Jobs {
+    pub fn new(store: Arc<dyn JobStore>) -> Self {
+        Jobs {
+            store,
+            running: Arc::default(),
+            limit: DEFAULT_JOB_LIMIT,
+        }
+    }
+
+    /// Refuse new jobs while `limit` are queued or running
+    ///
+    /// Proving itself is bounded by the notary's `ProvingPool`; this bounds how many jobs wait
+    /// for it.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Queue `work` as a new job and return it without waiting
+    ///
+    /// `work` gets the [`Progress`] its stages are recorded and published through. Fails with
+    /// [`SpawnError::Full`] while [`with_limit`](Self::with_limit) jobs are unfinished.
+    pub async fn spawn<F, Fut>(&self, work: F) -> Result<Job, SpawnError>
+    where
+        F: FnOnce(Progress) -> Fut,
+        Fut: Future<Output = Result<ProofResponse, JobError>> + Send + 'static,
+    {
+        let job = Job::new(hex::encode(rand::random::<[u8; 16]>()));
+
+        // The progress callback must not block: stages are recorded by the job task
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let work = work(Progress::new(move |event| {
+            let _ = progress_tx.send(event);
+        }));
+
+        let id = job.id.clone();
+        let store = self.store.clone();
+        let running = self.running.clone();
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let publisher = events.clone();
+        let (stored, is_stored) = oneshot::channel();
+
+        // Check the limit and register the job under one lock, so concurrent requests cannot
+        // overshoot it and the task cannot deregister before it is recorded
+        {
+            let mut jobs = self.running.lock().unwrap();
+            if jobs.len() >= self.limit {
+                return Err(SpawnError::Full { limit: self.limit });
+            }
+
+            let task = tokio::spawn(async move {
+                // Nothing to record until the job is stored
+                if is_stored.await.is_err() {
+                    return;
+                }
+                let store = store.as_ref();
+                record(store, &id, JobUpdate::Running).await;
+
+                tokio::pin!(work);
+                let outcome = loop {
+                    tokio::select! {
+                        Some(event) = progress_rx.recv() => {
+                            record_progress(store, &id, &publisher, event).await;
+                        }
+                        outcome = &mut work => break outcome,
+                    }
+                };
+                while let Ok(event) = progress_rx.try_recv() {
+                    record_progress(store, &id, &publisher, event).await;
+                }
+
+                let update = match outcome {
+                    Ok(result) => JobUpdate::Succeeded(result),
+                    Err(error) => JobUpdate::Failed(error),
+                };
+                running.lock().unwrap().remove(&id);
+                if let Some(job) = record(store, &id, update).await {
+                    let _ = publisher.send(JobEvent::Job(job));
+                }
+            });
+            jobs.insert(
+                job.id.clone(),
+                RunningJob {
+                    task: task.abort_handle(),
+                    events,
+                },
+            );
+        }
+
+        if let Err(e) = self.store.insert(job.clone()).await {
+            // Dropping `stored` ends the task
+            self.running.lock().unwrap().remove(&job.id);
+            return Err(e.into());
+        }
+        let _ = stored.send(());
+
+        Ok(job)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<Job>, JobStoreError> {
+        self.store.get(id).await
+    }
+
+    /// Events of an unfinished job; `None` once it finished
+    pub fn subscribe(&self, id: &str) -> Option<broadcast::Receiver<JobEvent>> {
+        let running = self.running.lock().unwrap();
+        running.get(id).map(|job| job.events.subscribe())
+    }
+
+    /// Stop the job's task and mark it cancelled; a finished job is returned unchanged
+    pub async fn cancel(&self, id: &str) -> Result<Option<Job>, JobStoreError> {
+        let running = self.running.lock().unwrap().remove(id);
+        if let Some(running) = &running {
+            running.task.abort();
+        }
+
+        let job = self.store.update(id, JobUpdate::Cancelled).await?;
+        if let (Some(running), Some(job)) = (running, &job) {
+            let _ = running.events.send(JobEvent::Job(job.clone()));
+        }
+        Ok(job)
+    }
+}
+
+async fn record(store: &dyn JobStore, id: &str, update: JobUpdate) -> Option<Job> {
+    match store.update(id, update).await {
+        Ok(job) => job,
+        Err(e) => {
+            tracing::error!("Failed to record job {} update: {}", id, e);
+            None
+        }
+    }
+}
+
+async fn record_progress(
+    store: &dyn JobStore,
+    id: &str,
+    publisher: &broadcast::Sender<JobEvent>,
+    event: ProgressEvent,
+) {
+    match event {
+        ProgressEvent::Started { stage, .. } => tracing::info!("⏱️ Job {}: {}", id, stage),
+        ProgressEvent::Completed {
+            stage, duration, ..
+        } => tracing::info!("⏱️ Job {}: {} took {:?}", id, stage, duration),
+    }
+    record(store, id, JobUpdate::Progress(event)).await;
+    // No subscribers is fine
+    let _ = publisher.send(JobEvent::Stage(event.into()));
+}
+
+type JobResult = Result<Json<Job>, (StatusCode, Json<ErrorResponse>)>;
+
+fn store_error(e: JobStoreError) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::error!("{}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse::new(
+            "INTERNAL_ERROR",
+            "Job state is unavailable",
+        )),
+    )
+}
+
+/// `503 QUEUE_FULL` with `Retry-After` when the limit is reached, `500` if the store failed
+pub(crate) fn spawn_error(e: SpawnError) -> Response {
+    match e {
+        SpawnError::Full { .. } => {
+            tracing::warn!("🚦 Refused proof job: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+                Json(ErrorResponse::new(
+                    "QUEUE_FULL",
+                    format!("The prover is busy ({}), retry later", e),
+                )),
+            )
+                .into_response()
+        }
+        SpawnError::Store(e) => store_error(e).into_response(),
+    }
+}
+
+fn not_found(id: &str) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse::new(
+            "JOB_NOT_FOUND",
+            format!("No job with id {}", id),
+        )),
+    )
+}
+
+/// GET /api/jobs/{id} - stage of a proof job, with its result or error once finished
+pub async fn get_job(State(jobs): State<Jobs>, Path(id): Path<String>) -> JobResult {
+    match jobs.get(&id).await.map_err(store_error)? {
+        Some(job) => Ok(Json(job)),
+        None => Err(not_found(&id)),
+    }
+}
+
+/// GET /api/jobs/{id}/events - Server-Sent Events stream of a proof job
+///
+/// Starts with a `job` event holding the job as stored, then sends a `stage` event each time a
+/// proof generation stage starts or completes, and a final `job` event once the job finished.
+/// The stream ends after that; for a finished job it is just the first event.
+pub async fn job_events(
+    State(jobs): State<Jobs>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, Json<ErrorResponse>)>
+{
+    // Subscribe before reading the job, so no event falls in between
+    let events = jobs.subscribe(&id);
+    let job = jobs
+        .get(&id)
+        .await
+        .map_err(store_error)?
+        .ok_or_else(|| not_found(&id))?;
+    let events = events.filter(|_| !job.stage.is_finished());
+
+    let updates = stream::unfold(events, |events| async move {
+        let mut events = events?;
+        loop {
+            match events.recv().await {
+                Ok(event) => return Some((event, Some(events))),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let stream = stream::once(async move { JobEvent::Job(job) })
+        .chain(updates)
+        .map(JobEvent::into_sse);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// POST /api/jobs/{id}/cancel - stop a queued or running proof job
+///
+/// Nothing is submitted on-chain after cancellation. Cancelling a finished job fails with
+/// `409 JOB_FINISHED`.
+pub async fn cancel_job(State(jobs): State<Jobs>, Path(id): Path<String>) -> JobResult {
+    let job = jobs
+        .cancel(&id)
+        .await
+        .map_err(store_error)?
+        .ok_or_else(|| not_found(&id))?;
+
+    if job.stage != JobStage::Cancelled {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(
+                "JOB_FINISHED",
+                format!("Job {} already finished ({:?})", id, job.stage),
+            )),
+        ));
+    }
+
+    tracing::info!("🛑 Cancelled proof job {}", id);
+    Ok(Json(job))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+    use notary::SubmissionMode;
+
+    fn jobs() -> Jobs {
+        Jobs::new(Arc::new(InMemoryJobStore::default()))
+    }
+
+    fn response() -> ProofResponse {
+        ProofResponse {
+            proof: "00".to_string(),
+            public_inputs: vec![],
+            submission: SubmissionMode::VerifyOnly,
+            transaction: None,
+            calldata: None,
+        }
+    }
+
+    async fn wait_until_finished(jobs: &Jobs, id: &str) -> Job {
+        loop {
+            let job = jobs.get(id).await.unwrap().unwrap();
+            if job.stage.is_finished() {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_reports_result() {
+        let jobs = jobs();
+
+        let job = jobs.spawn(|_| async { Ok(response()) }).await.unwrap();
+        assert_eq!(job.stage, JobStage::Queued);
+
+        let job = wait_until_finished(&jobs, &job.id).await;
+        assert_eq!(job.stage, JobStage::Succeeded);
+        assert_eq!(job.result.unwrap().proof, "00");
+        assert!(jobs.running.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_job_reports_error() {
+        let jobs = jobs();
+
+        let job = jobs
+            .spawn(|_| async {
+                Err(JobError {
+                    status: 422,
+                    error: ErrorResponse::new("SUBJECT_ALIVE", "alive"),
+                })
+            })
+            .await
+            .unwrap();
+
+        let job = wait_until_finished(&jobs, &job.id).await;
+        assert_eq!(job.stage, JobStage::Failed);
+        let json = serde_json::to_value(&job).unwrap();
+        assert_eq!(json["error"]["status"], 422);
+        assert_eq!(json["error"]["code"], "SUBJECT_ALIVE");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_the_job() {
+        let jobs = jobs();
+
+        let job = jobs
+            .spawn(|_| async {
+                std::future::pending::<()>().await;
+                Ok(response())
+            })
+            .await
+            .unwrap();
+
+        let (status, _) = cancel_job(State(jobs.clone()), Path("unknown".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let Json(cancelled) = cancel_job(State(jobs.clone()), Path(job.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(cancelled.stage, JobStage::Cancelled);
+        assert!(jobs.running.lock().unwrap().is_empty());
+
+        // Already finished: unchanged
+        let (status, Json(error)) = cancel_job(State(jobs.clone()), Path(job.id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(error.code, "JOB_FINISHED");
+    }
+
+    #[tokio::test]
+    async fn test_progress_is_recorded_and_streamed() {
+        let jobs = jobs();
+        let (start, started) = tokio::sync::oneshot::channel();
+
+        let job = jobs
+            .spawn(|progress| async move {
+                started.await.unwrap();
+                progress.stage(ProofStage::SrsSetup);
+                progress.stage(ProofStage::Proving);
+                progress.finish();
+                Ok(response())
+            })
+            .await
+            .unwrap();
+
+        let mut events = jobs.subscribe(&job.id).unwrap();
+        start.send(()).unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.recv().await {
+            received.push(event);
+        }
+        let stages: Vec<_> = received
+            .iter()
+            .filter_map(|event| match event {
+                JobEvent::Stage(event) => Some((event.stage, event.status)),
+                JobEvent::Job(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            stages,
+            [
+                (ProofStage::SrsSetup, StageStatus::Started),
+                (ProofStage::SrsSetup, StageStatus::Completed),
+                (ProofStage::Proving, StageStatus::Started),
+                (ProofStage::Proving, StageStatus::Completed),
+            ]
+        );
+        let Some(JobEvent::Job(finished)) = received.last() else {
+            panic!("expected the finished job last");
+        };
+        assert_eq!(finished.stage, JobStage::Succeeded);
+
+        // Stored with durations
+        let job = jobs.get(&job.id).await.unwrap().unwrap();
+        let recorded: Vec<_> = job.progress.iter().map(|record| record.stage).collect();
+        assert_eq!(recorded, [ProofStage::SrsSetup, ProofStage::Proving]);
+        assert!(job
+            .progress
+            .iter()
+            .all(|record| record.duration_ms.is_some()));
+
+        // A finished job streams just its final state
+        let sse = job_events(State(jobs.clone()), Path(job.id.clone()))
+            .await
+            .unwrap()
+            .into_response();
+        let body = axum::body::to_bytes(sse.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.starts_with("event: job\n"));
+        assert!(body.contains("\"stage\":\"succeeded\""));
+        assert_eq!(body.matches("event:").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_limit_refuses_new_jobs() {
+        let jobs = jobs().with_limit(1);
+
+        let pending = jobs.spawn(|_| std::future::pending()).await.unwrap();
+        let refused = jobs.spawn(|_| async { Ok(response()) }).await;
+        assert!(matches!(refused, Err(SpawnError::Full { limit: 1 })));
+
+        // A finished job frees its place
+        jobs.cancel(&pending.id).await.unwrap();
+        let job = jobs.spawn(|_| async { Ok(response()) }).await.unwrap();
+        assert_eq!(
+            wait_until_finished(&jobs, &job.id).await.stage,
+            JobStage::Succeeded
+        );
+    }
+
+    #[test]
+    fn test_finished_jobs_do_not_change() {
+        let mut job = Job::new("id".to_string());
+        assert!(job.apply(JobUpdate::Running));
+        assert!(job.apply(JobUpdate::Cancelled));
+        assert!(!job.apply(JobUpdate::Succeeded(response())));
+        assert_eq!(job.stage, JobStage::Cancelled);
+        assert!(job.result.is_none());
+    }
+}
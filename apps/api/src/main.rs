@@ -1,3 +1,5 @@
+mod commitment;
+mod jobs;
 mod proof;
 
 use axum::{
@@ -5,7 +7,9 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use jobs::{InMemoryJobStore, Jobs};
 use serde::Serialize;
+use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -51,11 +55,38 @@ async fn main() {
         .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
         .allow_headers(Any);
 
+    // Proof jobs run in the background; state is kept in process
+    let job_limit = std::env::var("PROOF_JOB_LIMIT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(jobs::DEFAULT_JOB_LIMIT);
+    let proof_jobs = Jobs::new(Arc::new(InMemoryJobStore::default())).with_limit(job_limit);
+    tracing::info!(
+        "🧮 Up to {} proof jobs at once, {} proving concurrently",
+        job_limit,
+        notary::ProvingPool::global().limit()
+    );
+
+    // Load the circuit, its SRS and verification key before accepting requests; a VK that does
+    // not hash to CIRCUIT_VK_HASH means circuits.json does not match the deployed verifier
+    let circuit = tokio::task::spawn_blocking(notary::CircuitContext::global)
+        .await
+        .unwrap();
+    if let Err(e) = circuit {
+        tracing::error!("❌ Circuit self-check failed: {}", e);
+        std::process::exit(1);
+    }
+
     // Build router
     let app = Router::new()
         .route("/health", get(health_check))
         .route("/api/generate-proof", post(proof::generate_proof))
-        .layer(cors);
+        .route("/api/jobs/:id", get(jobs::get_job))
+        .route("/api/jobs/:id/events", get(jobs::job_events))
+        .route("/api/jobs/:id/cancel", post(jobs::cancel_job))
+        .route("/api/commitment", post(commitment::compute_commitment))
+        .layer(cors)
+        .with_state(proof_jobs);
 
     // Start server - bind address from environment or default
     // Render uses PORT env var, so check that first
@@ -70,6 +101,10 @@ async fn main() {
     tracing::info!("🔗 Endpoints:");
     tracing::info!("   GET  /health");
     tracing::info!("   POST /api/generate-proof");
+    tracing::info!("   GET  /api/jobs/:id");
+    tracing::info!("   GET  /api/jobs/:id/events");
+    tracing::info!("   POST /api/jobs/:id/cancel");
+    tracing::info!("   POST /api/commitment");
 
     let listener = tokio::net::TcpListener::bind(&bind_address).await.unwrap();
     axum::serve(listener, app).await.unwrap();
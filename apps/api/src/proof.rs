@@ -1,310 +1,430 @@
-use axum::{http::StatusCode, Json};
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use notary::proof_gen::generate_death_proof_with_config;
+use notary::{
+    ColombiaRegistry, EthAddress, NotaryError, Nuip, ProofGenConfig, Progress, RegistryAdapter,
+    Salt, Submission, SubmissionMode,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::jobs::{spawn_error, Job, JobError, Jobs};
+
 /// Request body for proof generation
 #[derive(Debug, Deserialize)]
 pub struct ProofRequest {
-    /// Ethereum address of the heir (hex string without 0x prefix)
-    pub recipient: String,
-    /// National ID number (NUIP)
-    pub nuip: String,
-    /// Salt for identity commitment (hex string without 0x prefix)
-    pub salt: String,
+    /// Ethereum address of the heir (hex string, 0x optional)
+    pub recipient: EthAddress,
+    /// National ID number (NUIP), at most 15 characters
+    pub nuip: Nuip,
+    /// Salt for identity commitment (64 hex characters, 0x optional)
+    pub salt: Salt,
     /// Ethereum address of the testator (delegated account)
-    pub testator_address: String,
+    pub testator_address: EthAddress,
+    /// `verify_only`, `submit`, `calldata` or `dry_run`; omitted, the server's default
+    /// ([`SubmissionMode::from_env`]: `verify_only` with a remote notary, otherwise `submit`)
+    #[serde(default)]
+    pub submission: Option<SubmissionMode>,
 }
 
-/// Response body for proof generation
-#[derive(Debug, Serialize)]
+/// Result of a proof generation job
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofResponse {
     /// Hex-encoded ZK proof
     pub proof: String,
-    /// Public inputs as array of hex strings (116 fields)
-    pub public_inputs: Vec<String>
+    /// Public inputs as array of hex strings (148 fields)
+    pub public_inputs: Vec<String>,
+    /// Submission mode the request ran with
+    pub submission: SubmissionMode,
+    /// Confirmed transaction, when the server submitted (`submit`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionResponse>,
+    /// Call for the heir's wallet to send (`calldata`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calldata: Option<CalldataResponse>,
+}
+
+/// Transaction sent by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResponse {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub gas_used: u64,
+}
+
+/// `proveDeathAndRegisterHeir` call, ABI-encoded, for the testator's delegated account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalldataResponse {
+    /// Testator's delegated account (0x-prefixed)
+    pub to: String,
+    /// ABI-encoded calldata (0x-prefixed)
+    pub data: String,
 }
 
 /// Error response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
+    /// Stable machine-readable error code (e.g. `SUBJECT_ALIVE`)
+    pub code: String,
     pub error: String,
+    /// ProofHeir custom error name for `CONTRACT_REVERTED` and `PREFLIGHT_FAILED` (e.g. `IdentityNotRegistered`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert: Option<String>,
 }
 
-/// Generate a ZK proof for death certificate verification
+impl ErrorResponse {
+    pub(crate) fn new(code: &str, error: impl Into<String>) -> Self {
+        ErrorResponse {
+            code: code.to_string(),
+            error: error.into(),
+            revert: None,
+        }
+    }
+
+    /// Error response for request validation failures caught by the API itself
+    pub(crate) fn invalid_input(error: String) -> Self {
+        Self::new("INVALID_INPUT", error)
+    }
+}
+
+/// Reject a request body that is not valid JSON or fails field validation (NUIP, salt, addresses)
 ///
-/// This endpoint:
-/// 1. Validates input parameters
-/// 2. Calls the notary proof generation
-/// 3. Returns proof and public inputs ready for smart contract
+/// The message names the offending field, e.g. `salt: Salt must be 32 bytes ...`.
+pub(crate) fn reject_body(rejection: JsonRejection) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::error!("Invalid request body: {}", rejection.body_text());
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse::invalid_input(rejection.body_text())),
+    )
+}
+
+/// Queue a ZK proof generation job for death certificate verification
+///
+/// Validates the request and answers `202 Accepted` with the queued job right away
+/// (MPC-TLS and proving take minutes). Poll `GET /api/jobs/{id}` for the stage and result, or
+/// follow `GET /api/jobs/{id}/events`. While the job limit is reached, answers
+/// `503 QUEUE_FULL` with `Retry-After`.
 pub async fn generate_proof(
-    Json(request): Json<ProofRequest>,
-) -> Result<Json<ProofResponse>, (StatusCode, Json<ErrorResponse>)> {
+    State(jobs): State<Jobs>,
+    payload: Result<Json<ProofRequest>, JsonRejection>,
+) -> Result<(StatusCode, [(header::HeaderName, String); 1], Json<Job>), Response> {
     tracing::info!("Received proof generation request");
 
-    // Helper to strip 0x prefix if present
-    let strip_0x = |s: &str| -> String {
-        s.strip_prefix("0x")
-            .or_else(|| s.strip_prefix("0X"))
-            .unwrap_or(s)
-            .to_string()
-    };
+    // Addresses, NUIP and salt are parsed and length-checked during deserialization
+    let Json(request) = payload.map_err(|rejection| reject_body(rejection).into_response())?;
 
-    // Validate and parse recipient address (20 bytes) - accept with or without 0x
-    let recipient = hex::decode(strip_0x(&request.recipient))
+    // The registry must receive the NUIP exactly as it is committed and proven
+    ColombiaRegistry::from_env()
+        .validate_id(&request.nuip)
         .map_err(|e| {
-            tracing::error!("Invalid recipient hex: {}", e);
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Invalid recipient address: {}", e),
-                }),
-            )
-        })?
-        .try_into()
-        .map_err(|v: Vec<u8>| {
-            tracing::error!("Recipient must be exactly 20 bytes, got {}", v.len());
             (
                 StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Recipient must be exactly 20 bytes, got {}", v.len()),
-                }),
-            )
-        })?;
-
-    // Validate and parse salt (32 bytes) - accept with or without 0x
-    let salt = hex::decode(strip_0x(&request.salt))
-        .map_err(|e| {
-            tracing::error!("Invalid salt hex: {}", e);
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Invalid salt: {}", e),
-                }),
-            )
-        })?
-        .try_into()
-        .map_err(|v: Vec<u8>| {
-            tracing::error!("Salt must be exactly 32 bytes, got {}", v.len());
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Salt must be exactly 32 bytes, got {}", v.len()),
-                }),
-            )
-        })?;
-
-    // Validate and parse testator address (20 bytes) - accept with or without 0x
-    let testator_address = hex::decode(strip_0x(&request.testator_address))
-        .map_err(|e| {
-            tracing::error!("Invalid testator address hex: {}", e);
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Invalid testator address: {}", e),
-                }),
-            )
-        })?
-        .try_into()
-        .map_err(|v: Vec<u8>| {
-            tracing::error!("Testator address must be exactly 20 bytes, got {}", v.len());
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Testator address must be exactly 20 bytes, got {}", v.len()),
-                }),
+                Json(ErrorResponse::invalid_input(format!("nuip: {}", e))),
             )
+                .into_response()
         })?;
 
     tracing::info!("✅ Input validation passed");
-    tracing::info!("   Recipient: 0x{}", hex::encode(&recipient));
-    tracing::info!("   Testator: 0x{}", hex::encode(&testator_address));
+    tracing::info!("   Recipient: {}", request.recipient);
+    tracing::info!("   Testator: {}", request.testator_address);
+    tracing::info!("   Submission: {:?}", request.submission);
     // NUIP and salt are sensitive - not logged
 
+    let job = jobs
+        .spawn(|progress| run_proof(request, progress))
+        .await
+        .map_err(spawn_error)?;
+    tracing::info!("📋 Queued proof job {}", job.id);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/api/jobs/{}", job.id))],
+        Json(job),
+    ))
+}
+
+/// Generate a ZK proof for death certificate verification - the body of a proof job
+///
+/// This job:
+/// 1. Calls the notary proof generation
+/// 2. Submits on-chain, returns calldata, simulates or only verifies, per the request's `submission`
+/// 3. Returns proof and public inputs ready for smart contract
+///
+/// Stages are reported to `progress` as they start and complete.
+pub async fn run_proof(request: ProofRequest, progress: Progress) -> Result<ProofResponse, JobError> {
     // Generate proof using the notary library
     tracing::info!("🚀 Starting proof generation...");
     
-    let result = notary::proof_gen::generate_death_proof(recipient, request.nuip, salt, testator_address)
-        .await
-        .map_err(|e| {
-            let error_str = e.to_string();
-            tracing::error!("Proof generation failed: {}", error_str);
-            
-            // Classify errors and return appropriate HTTP status codes
-            let (status, user_message) = classify_proof_error(&error_str);
-            
-            (
-                status,
-                Json(ErrorResponse {
-                    error: user_message,
-                }),
-            )
-        })?;
+    let defaults = ProofGenConfig::from_env();
+    let config = ProofGenConfig {
+        submission: request.submission.unwrap_or(defaults.submission),
+        progress,
+        ..defaults
+    };
+    let result = generate_death_proof_with_config(
+        request.recipient,
+        request.nuip,
+        request.salt,
+        request.testator_address,
+        &config,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Proof generation failed [{}]: {}", e.code(), e);
+        
+        // Classify errors and return appropriate HTTP status codes
+        let (status, user_message) = classify_proof_error(&e);
+        
+        JobError {
+            status: status.as_u16(),
+            error: ErrorResponse {
+                code: e.code().to_string(),
+                error: user_message,
+                revert: match &e {
+                    NotaryError::ContractReverted(revert) | NotaryError::Preflight(revert) => {
+                        Some(revert.name().to_string())
+                    }
+                    _ => None,
+                },
+            },
+        }
+    })?;
 
     tracing::info!("✅ Proof generation successful!");
     tracing::info!("   Proof size: {} bytes", result.proof.len());
     tracing::info!("   VK size: {} bytes", result.vk.len());
     tracing::info!("   Public inputs: {} fields", result.public_inputs.len());
 
+    let (transaction, calldata) = match result.submission {
+        Submission::Skipped | Submission::Simulated => (None, None),
+        Submission::Submitted(receipt) => (
+            Some(TransactionResponse {
+                tx_hash: format!("0x{}", hex::encode(receipt.tx_hash)),
+                block_number: receipt.block_number,
+                gas_used: receipt.gas_used,
+            }),
+            None,
+        ),
+        Submission::Calldata { to, data } => (
+            None,
+            Some(CalldataResponse {
+                to: format!("0x{}", hex::encode(to)),
+                data: format!("0x{}", hex::encode(data)),
+            }),
+        ),
+    };
+
     // Return the proof bundle
-    Ok(Json(ProofResponse {
+    Ok(ProofResponse {
         proof: hex::encode(&result.proof),
         public_inputs: result.public_inputs,
-    }))
+        submission: config.submission,
+        transaction,
+        calldata,
+    })
 }
 
-/// Classify proof generation errors and return appropriate HTTP status codes
-/// This prevents the service from crashing due to unhandled errors in prover/verifier
-fn classify_proof_error(error: &str) -> (StatusCode, String) {
-    let error_lower = error.to_lowercase();
-    
-    // ============================================================================
-    // 1. BAD REQUEST (400) - Client input validation errors
-    // ============================================================================
-    if error_lower.contains("invalid nuip")
-        || error_lower.contains("uri must use https")
-        || error_lower.contains("uri must have authority")
-        || error_lower.contains("invalid recipient")
-        || error_lower.contains("invalid salt")
-        || error_lower.contains("invalid testator")
-    {
-        return (
+/// Map a notary error to the HTTP status code and user-facing message returned by the API
+fn classify_proof_error(error: &NotaryError) -> (StatusCode, String) {
+    match error {
+        NotaryError::InvalidInput(_) => (
             StatusCode::BAD_REQUEST,
             format!("Invalid request parameters: {}", error),
-        );
-    }
-    
-    // ============================================================================
-    // 2. UNPROCESSABLE ENTITY (422) - Business logic errors (subject is alive)
-    // ============================================================================
-    if error_lower.contains("subject is alive")
-        || error_lower.contains("proof of death")
-        || error_lower.contains("vigente (vivo)")
-    {
-        return (
+        ),
+        NotaryError::SubjectAlive => (
             StatusCode::UNPROCESSABLE_ENTITY,
             "Cannot generate proof: subject is still alive according to registry".to_string(),
-        );
-    }
-    
-    // ============================================================================
-    // 3. BAD GATEWAY (502) - External service errors (Civil Registry, TLS)
-    // ============================================================================
-    if error_lower.contains("failed to query civil registry")
-        || error_lower.contains("mpc-tls request failed")
-        || error_lower.contains("tls")
-        || error_lower.contains("certificate")
-        || error_lower.contains("connection refused")
-        || error_lower.contains("dns")
-        || error_lower.contains("lookup host")
-        || error_lower.contains("connection reset")
-        || error_lower.contains("timeout")
-    {
-        return (
+        ),
+        NotaryError::UntrustedRegistry(_) => (
+            StatusCode::FORBIDDEN,
+            format!("Registry not accepted by the notary: {}", error),
+        ),
+        NotaryError::RegistryUnreachable(_) | NotaryError::MpcTls(_) => (
             StatusCode::BAD_GATEWAY,
             format!("External service error: Unable to connect to civil registry. Please try again later. Details: {}", error),
-        );
-    }
-    
-    // ============================================================================
-    // 4. UNPROCESSABLE ENTITY (422) - ZK proof generation/verification errors
-    // ============================================================================
-    if error_lower.contains("prover error")
-        || error_lower.contains("verifier error")
-        || error_lower.contains("zk proof")
-        || error_lower.contains("witness")
-        || error_lower.contains("bytecode")
-        || error_lower.contains("srs")
-        || error_lower.contains("verification key")
-        || error_lower.contains("commitment")
-        || error_lower.contains("hash")
-        || error_lower.contains("noir")
-        || error_lower.contains("barretenberg")
-        || error_lower.contains("computed hash does not match")
-        || error_lower.contains("received commitments")
-        || error_lower.contains("received secrets")
-        || error_lower.contains("no responses found")
-        || error_lower.contains("vigencia field not found")
-        || error_lower.contains("status_commitment")
-        || error_lower.contains("mpc-tls commitment")
-        || error_lower.contains("proof too short")
-    {
-        return (
+        ),
+        NotaryError::CommitmentMismatch(_)
+        | NotaryError::PublicInputsMismatch(_)
+        | NotaryError::ZkProof(_)
+        | NotaryError::InvalidProof(_) => (
             StatusCode::UNPROCESSABLE_ENTITY,
             format!("ZK proof generation failed: {}. This may indicate an incompatible data format from the registry.", error),
-        );
-    }
-    
-    // ============================================================================
-    // 5. SERVICE UNAVAILABLE (503) - Blockchain/on-chain errors
-    // ============================================================================
-    if error_lower.contains("verifier_private_key")
-        || error_lower.contains("rpc")
-        || error_lower.contains("transaction")
-        || error_lower.contains("send transaction")
-        || error_lower.contains("receipt")
-        || error_lower.contains("reverted")
-        || error_lower.contains("gas")
-        || error_lower.contains("contract")
-        || error_lower.contains("provedeathandregisterheir")
-    {
-        return (
+        ),
+        NotaryError::ContractReverted(revert) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("The ProofHeir contract rejected the proof: {}", revert),
+        ),
+        NotaryError::Preflight(revert) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("The ProofHeir contract would reject this proof: {}", revert),
+        ),
+        NotaryError::OnChain(_) => (
             StatusCode::SERVICE_UNAVAILABLE,
             format!("Blockchain service temporarily unavailable: {}. Please try again later.", error),
-        );
+        ),
+        // The full error is logged by the caller; return a generic message
+        NotaryError::Internal(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "An unexpected error occurred during proof generation. The team has been notified.".to_string(),
+        ),
     }
-    
-    // ============================================================================
-    // 6. INTERNAL SERVER ERROR (500) - Unexpected/unknown errors
-    // ============================================================================
-    // Log the full error for debugging but return a generic message
-    tracing::error!("Unclassified error (please add classification): {}", error);
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        "An unexpected error occurred during proof generation. The team has been notified.".to_string(),
-    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::jobs::InMemoryJobStore;
+    use notary::ProofHeirError;
+    use std::sync::Arc;
 
     #[test]
     fn test_proof_request_deserialization() {
         let json = r#"{
             "recipient": "abababababababababababababababababababab",
             "nuip": "454545454",
-            "salt": "1111111111111111111111111111111111111111111111111111111111111111"
+            "salt": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "testator_address": "0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
         }"#;
 
         let request: ProofRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(request.recipient, "abababababababababababababababababababab");
-        assert_eq!(request.nuip, "454545454");
-        assert_eq!(
-            request.salt,
-            "1111111111111111111111111111111111111111111111111111111111111111"
-        );
+        assert_eq!(request.recipient, EthAddress::from([0xab; 20]));
+        assert_eq!(request.nuip.as_str(), "454545454");
+        assert_eq!(request.salt, Salt::from([0x11; 32]));
+        assert_eq!(request.testator_address, EthAddress::from([0xcd; 20]));
+    }
+
+    #[test]
+    fn test_submission_mode_deserialization() {
+        let mut json = serde_json::json!({
+            "recipient": "abababababababababababababababababababab",
+            "nuip": "454545454",
+            "salt": "1111111111111111111111111111111111111111111111111111111111111111",
+            "testator_address": "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        });
+
+        // Omitted: the server's default applies
+        let request: ProofRequest = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(request.submission, None);
+
+        json["submission"] = "calldata".into();
+        let request: ProofRequest = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(request.submission, Some(SubmissionMode::Calldata));
+
+        json["submission"] = "broadcast".into();
+        assert!(serde_json::from_value::<ProofRequest>(json).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_returns_503() {
+        let jobs = Jobs::new(Arc::new(InMemoryJobStore::default())).with_limit(1);
+        jobs.spawn(|_| std::future::pending()).await.unwrap();
+
+        let request = serde_json::from_value(serde_json::json!({
+            "recipient": "abababababababababababababababababababab",
+            "nuip": "454545454",
+            "salt": "1111111111111111111111111111111111111111111111111111111111111111",
+            "testator_address": "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        }))
+        .unwrap();
+        let response = generate_proof(State(jobs), Ok(Json(request)))
+            .await
+            .unwrap_err();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+    }
+
+    #[tokio::test]
+    async fn test_nuip_the_registry_would_rewrite_is_rejected() {
+        let jobs = Jobs::new(Arc::new(InMemoryJobStore::default()));
+
+        // Sent as a JSON number, "0123" would reach the registry as 123
+        let request = serde_json::from_value(serde_json::json!({
+            "recipient": "abababababababababababababababababababab",
+            "nuip": "0123",
+            "salt": "1111111111111111111111111111111111111111111111111111111111111111",
+            "testator_address": "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        }))
+        .unwrap();
+        let response = generate_proof(State(jobs), Ok(Json(request)))
+            .await
+            .unwrap_err();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    fn rejection(body: serde_json::Value) -> String {
+        let (status, Json(error)) =
+            reject_body(Json::<ProofRequest>::from_bytes(body.to_string().as_bytes()).unwrap_err());
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.code, "INVALID_INPUT");
+        error.error
     }
 
     #[test]
-    fn test_recipient_validation() {
-        // Valid 20-byte hex
-        let valid = hex::decode("abababababababababababababababababababab").unwrap();
-        assert_eq!(valid.len(), 20);
-
-        // Invalid length
-        let invalid = hex::decode("abab").unwrap();
-        assert_ne!(invalid.len(), 20);
+    fn test_invalid_fields_are_rejected_by_name() {
+        let valid = serde_json::json!({
+            "recipient": "abababababababababababababababababababab",
+            "nuip": "454545454",
+            "salt": "1111111111111111111111111111111111111111111111111111111111111111",
+            "testator_address": "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        });
+        assert!(Json::<ProofRequest>::from_bytes(valid.to_string().as_bytes()).is_ok());
+
+        let mut body = valid.clone();
+        body["recipient"] = "abab".into();
+        let message = rejection(body);
+        assert!(message.contains("recipient"), "{}", message);
+        assert!(message.contains("20 bytes"), "{}", message);
+
+        let mut body = valid.clone();
+        body["salt"] = "1111".into();
+        let message = rejection(body);
+        assert!(message.contains("salt"), "{}", message);
+        assert!(message.contains("32 bytes"), "{}", message);
+
+        // Longer than the circuit's str<15>: rejected, not truncated
+        let mut body = valid.clone();
+        body["nuip"] = "1234567890123456".into();
+        let message = rejection(body);
+        assert!(message.contains("nuip"), "{}", message);
+        assert!(message.contains("at most 15"), "{}", message);
+
+        let mut body = valid;
+        body.as_object_mut().unwrap().remove("testator_address");
+        assert!(rejection(body).contains("testator_address"));
     }
 
     #[test]
-    fn test_salt_validation() {
-        // Valid 32-byte hex
-        let valid =
-            hex::decode("1111111111111111111111111111111111111111111111111111111111111111")
-                .unwrap();
-        assert_eq!(valid.len(), 32);
-
-        // Invalid length
-        let invalid = hex::decode("1111").unwrap();
-        assert_ne!(invalid.len(), 32);
+    fn test_error_classification() {
+        let (status, _) = classify_proof_error(&NotaryError::SubjectAlive);
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (status, _) = classify_proof_error(&NotaryError::InvalidInput("bad nuip".into()));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = classify_proof_error(&NotaryError::RegistryUnreachable("timeout".into()));
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+
+        let (status, _) = classify_proof_error(&NotaryError::UntrustedRegistry("evil.example".into()));
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, message) = classify_proof_error(&NotaryError::ContractReverted(
+            ProofHeirError::IdentityNotRegistered,
+        ));
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(message.contains("identity commitment"));
+
+        let (status, _) = classify_proof_error(&NotaryError::Preflight(
+            ProofHeirError::IdentityNotRegistered,
+        ));
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Substrings like "hash" or "gas" no longer influence classification
+        let (status, _) = classify_proof_error(&NotaryError::OnChain("hash mismatch".into()));
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
     }
 }
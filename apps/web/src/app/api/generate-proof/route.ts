@@ -3,8 +3,8 @@ import { NextResponse } from 'next/server'
 /**
  * POST /api/generate-proof
  * 
- * Generates a ZK proof for the "Proof of Death" claim.
- * This endpoint calls the Rust notary package to:
+ * Queues a ZK proof job for the "Proof of Death" claim and returns it immediately.
+ * Poll GET /api/proof-job?id=<job id> for the result. The job calls the Rust notary package to:
  * 1. Connect to civil registry via MPC-TLS
  * 2. Extract authenticated death certificate data
  * 3. Generate ZK proof using Noir circuit
@@ -17,10 +17,10 @@ import { NextResponse } from 'next/server'
  *   "salt": "0x1111..."         // 32-byte salt (64 hex chars)
  * }
  * 
- * Response:
+ * Response (202):
  * {
- *   "proof": "0x...",                    // Hex-encoded ZK proof
- *   "publicInputs": ["0x...", ...]       // 116 hex-encoded 32-byte fields
+ *   "id": "9f2c...",                     // Job id
+ *   "stage": "queued"                    // queued | running | succeeded | failed | cancelled
  * }
  */
 export async function POST(request: Request) {
@@ -95,8 +95,8 @@ export async function POST(request: Request) {
             )
         }
 
-        const data = await response.json()
-        return NextResponse.json(data, { status: 200 })
+        const job = await response.json()
+        return NextResponse.json(job, { status: response.status })
 
     } catch (error: any) {
         console.error('Proof generation error:', error)
@@ -0,0 +1,47 @@
+import { NextRequest, NextResponse } from 'next/server'
+
+/**
+ * GET /api/proof-job?id=<job id>
+ *
+ * Reports the stage of a proof job queued by POST /api/generate-proof.
+ *
+ * Response:
+ * {
+ *   "id": "9f2c...",
+ *   "stage": "succeeded",                   // queued | running | succeeded | failed | cancelled
+ *   "result": { "proof": "...", "public_inputs": ["0x...", ...], ... },  // once succeeded
+ *   "error": { "status": 422, "code": "SUBJECT_ALIVE", "error": "..." }  // once failed
+ * }
+ */
+export async function GET(request: NextRequest) {
+    const { searchParams } = new URL(request.url)
+    const id = searchParams.get('id')
+
+    if (!id || !/^[0-9a-f]+$/.test(id)) {
+        return NextResponse.json(
+            { error: 'Missing or invalid id parameter' },
+            { status: 400 }
+        )
+    }
+
+    try {
+        const rustApiUrl = process.env.RUST_API_URL || 'http://localhost:3001'
+        const response = await fetch(`${rustApiUrl}/api/jobs/${id}`, { cache: 'no-store' })
+
+        const data = await response.json().catch(() => ({ error: 'Unknown error' }))
+        if (!response.ok) {
+            return NextResponse.json(
+                { error: data.error || `Rust API error: ${response.statusText}` },
+                { status: response.status }
+            )
+        }
+
+        return NextResponse.json(data, { status: 200 })
+    } catch (error: any) {
+        console.error('Proof job lookup error:', error)
+        return NextResponse.json(
+            { error: error.message || 'Internal server error' },
+            { status: 500 }
+        )
+    }
+}
@@ -234,7 +234,21 @@ export function ClaimCard() {
                     throw new Error(errorData.error || 'Failed to generate proof')
                 }
 
-                const proofData = await proofRes.json()
+                // Proof generation runs as a job on the server (takes minutes): poll until it finishes
+                let job = await proofRes.json()
+                while (job.stage === 'queued' || job.stage === 'running') {
+                    await new Promise(resolve => setTimeout(resolve, 3000))
+                    const jobRes = await fetch(`/api/proof-job?id=${job.id}`)
+                    if (!jobRes.ok) {
+                        const errorData = await jobRes.json()
+                        throw new Error(errorData.error || 'Failed to check proof status')
+                    }
+                    job = await jobRes.json()
+                }
+
+                if (job.stage !== 'succeeded') {
+                    throw new Error(job.error?.error || `Proof generation ${job.stage}`)
+                }
 
                 // STEP 2: Wait for heir registration
                 setStatus('waiting')
@@ -42,7 +42,7 @@ contract ProofHeir {
 
     /// @custom:storage-location erc7201:proofheir.storage.main
     struct ProofHeirStorage {
-        /// @dev Commitment of the owner's identity: keccak256(RealID || Salt)
+        /// @dev Commitment of the owner's identity: sha256(RealID zero-padded to 15 bytes || Salt)
         bytes32 identityCommitment;
         /// @dev Address of the registered heir who can claim assets
         address heir;
@@ -53,6 +53,10 @@ contract ProofHeir {
     bytes32 private constant PROOFHEIR_STORAGE_LOCATION = 
         0x8d0a5b5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e5e00;
 
+    /// @dev Public input fields of the circuit, one per byte:
+    ///      20 (recipient) + 32 (server_hash) + 32 (id_commitment) + 32 (status_commitment) + 32 (nuip_commitment)
+    uint256 public constant PUBLIC_INPUTS_LENGTH = 148;
+
     /*//////////////////////////////////////////////////////////////
                                 EVENTS
     //////////////////////////////////////////////////////////////*/
@@ -86,8 +90,8 @@ contract ProofHeir {
 
     /// @notice Thrown when public inputs array has incorrect length
     /// @param provided The length of the provided array
-    /// @param minimum The minimum required length
-    error InvalidPublicInputsLength(uint256 provided, uint256 minimum);
+    /// @param expected The length the circuit emits (PUBLIC_INPUTS_LENGTH)
+    error InvalidPublicInputsLength(uint256 provided, uint256 expected);
 
     /// @notice Thrown when the identity commitment in proof doesn't match registered commitment
     /// @param proofCommitment The identity commitment from the proof
@@ -146,7 +150,7 @@ contract ProofHeir {
 
     /**
      * @notice Registers the identity commitment for the account owner (Step 1 of inheritance setup).
-     * @param _identityCommitment The keccak256 hash of (user's real ID || salt)
+     * @param _identityCommitment sha256(user's real ID zero-padded to 15 bytes || 32-byte salt), as the circuit computes it
      * 
      * @dev Requirements:
      *      - Can only be called by the account owner (via EIP-7702 delegation)
@@ -157,8 +161,9 @@ contract ProofHeir {
      *      the person is alive and owns the identity being committed.
      * 
      * @dev Example:
-     *      If Bob's ID is "123456789" and salt is "mysecret", then:
-     *      _identityCommitment = keccak256(abi.encodePacked("123456789", "mysecret"))
+     *      If Bob's ID is "123456789" and salt is bytes32(0x11..11), then:
+     *      _identityCommitment = sha256(abi.encodePacked(bytes15("123456789"), salt))
+     *      (`notary commitment` and `POST /api/commitment` compute the same value)
      */
     function registerIdentity(bytes32 _identityCommitment) external onlyOwner {
         ProofHeirStorage storage $ = _getProofHeirStorage();
@@ -170,13 +175,16 @@ contract ProofHeir {
     /**
      * @notice Verifies a death proof and registers the heir (Step 2 of inheritance claim).
      * @param proof The ZK proof bytes generated by the Noir circuit
-     * @param publicInputs Array of 116 field elements representing the public inputs
+     * @param publicInputs Array of 148 field elements representing the public inputs
      * 
-     * @dev Public Inputs Format (116 fields total, each byte serialized as a 32-byte field element):
+     * @dev Public Inputs Format (148 fields total, each byte serialized as a 32-byte field element):
      *      - [0-19]:    recipient address (20 bytes)
      *      - [20-51]:   server_hash (32 bytes) - SHA-256 hash of trusted server domain
-     *      - [52-83]:   id_commitment (32 bytes) - keccak256(realID || salt)
-     *      - [84-115]:  status_commitment (32 bytes) - keccak256(status || blinder)
+     *      - [52-83]:   id_commitment (32 bytes) - sha256(realID zero-padded to 15 bytes || salt)
+     *      - [84-115]:  status_commitment (32 bytes) - MPC-TLS sha256 commitment to the registry status
+     *      - [116-147]: nuip_commitment (32 bytes) - MPC-TLS commitment to the NUIP sent to the registry,
+     *                   bound to id_commitment inside the circuit. It is blinded, so the contract
+     *                   does not read it; the circuit and the notary check it and the verifier binds it to the proof
      * 
      * @dev Security Checks Performed:
      *      1. Recipient Binding: Extracts and validates the heir address from the proof
@@ -186,7 +194,7 @@ contract ProofHeir {
      * 
      * @dev Requirements:
      *      - Identity must be registered first via registerIdentity()
-     *      - publicInputs must contain exactly 116 field elements
+     *      - publicInputs must contain exactly 148 field elements (PUBLIC_INPUTS_LENGTH)
      *      - The proof must be valid according to the verifier contract
      * 
      * @dev Access Control:
@@ -198,8 +206,9 @@ contract ProofHeir {
         if ($.identityCommitment == bytes32(0)) revert IdentityNotRegistered();
         
         // Circuit serializes each byte as a 32-byte field element
-        // Total: 20 (recipient) + 32 (server_hash) + 32 (id_commitment) + 32 (status_commitment) = 116 fields
-        if (publicInputs.length < 116) revert InvalidPublicInputsLength(publicInputs.length, 116);
+        if (publicInputs.length != PUBLIC_INPUTS_LENGTH) {
+            revert InvalidPublicInputsLength(publicInputs.length, PUBLIC_INPUTS_LENGTH);
+        }
         
 
         // --- 1. Security Check: Recipient Binding ---
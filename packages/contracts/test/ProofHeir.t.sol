@@ -21,6 +21,7 @@ contract ProofHeirTest is Test {
     // Constants for circuit compatibility
     string constant TRUSTED_SERVER_DOMAIN = "civil-registry-mock.onrender.com        "; // 40 chars padded
     bytes32 constant SERVER_HASH = 0x5ddeed97a3950919f49ab7e413eeb1272467a129b6a75b59ac81da0516bdfb92;
+    bytes32 constant NUIP_COMMITMENT = bytes32(uint256(0x777)); // Dummy, only the circuit checks it
     bytes32 IDENTITY_COMMITMENT;
 
     function setUp() public {
@@ -39,7 +40,7 @@ contract ProofHeirTest is Test {
     }
 
     /**
-     * @dev Builds 116-field public inputs matching circuit serialization format.
+     * @dev Builds 148-field public inputs matching circuit serialization format.
      */
     function _buildPublicInputs(
         address recipient,
@@ -47,7 +48,7 @@ contract ProofHeirTest is Test {
         bytes32 idCommitment,
         bytes32 statusCommitment
     ) internal pure returns (bytes32[] memory) {
-        bytes32[] memory inputs = new bytes32[](116);
+        bytes32[] memory inputs = new bytes32[](148);
         
         // Recipient (20 bytes → fields 0-19)
         bytes20 recipientBytes = bytes20(recipient);
@@ -69,6 +70,11 @@ contract ProofHeirTest is Test {
         for (uint i = 0; i < 32; i++) {
             inputs[84 + i] = bytes32(uint256(uint8(statusCommitment[i])));
         }
+
+        // NUIP commitment (32 bytes → fields 116-147)
+        for (uint i = 0; i < 32; i++) {
+            inputs[116 + i] = bytes32(uint256(uint8(NUIP_COMMITMENT[i])));
+        }
         
         return inputs;
     }
@@ -79,7 +85,7 @@ contract ProofHeirTest is Test {
 
         address recipient = ALICE_ADDRESS; 
         
-        // Build 116-field public inputs
+        // Build 148-field public inputs
         bytes memory proof = hex"1234";
         bytes32[] memory publicInputs = _buildPublicInputs(
             recipient,
@@ -143,4 +149,37 @@ contract ProofHeirTest is Test {
         assertEq(tokenA.balanceOf(attacker), 0, "Attacker should not receive tokens");
         assertEq(tokenA.balanceOf(BOB_ADDRESS), 0, "Bob should have 0 tokens");
     }
+
+    function testRejectsWrongPublicInputsLength() public {
+        bytes memory proof = hex"1234";
+        bytes32[] memory publicInputs = _buildPublicInputs(
+            ALICE_ADDRESS,
+            SERVER_HASH,
+            IDENTITY_COMMITMENT,
+            bytes32(uint256(0x999))
+        );
+
+        vm.signAndAttachDelegation(address(proofHeir), BOB_PK);
+
+        vm.prank(BOB_ADDRESS);
+        ProofHeir(BOB_ADDRESS).registerIdentity(IDENTITY_COMMITMENT);
+
+        // Old 116-field layout, without nuip_commitment
+        bytes32[] memory truncated = new bytes32[](116);
+        for (uint i = 0; i < 116; i++) {
+            truncated[i] = publicInputs[i];
+        }
+        vm.prank(ALICE_ADDRESS);
+        vm.expectRevert(abi.encodeWithSelector(ProofHeir.InvalidPublicInputsLength.selector, 116, 148));
+        ProofHeir(BOB_ADDRESS).proveDeathAndRegisterHeir(proof, truncated);
+
+        // Extra trailing fields are rejected as well
+        bytes32[] memory padded = new bytes32[](149);
+        for (uint i = 0; i < 148; i++) {
+            padded[i] = publicInputs[i];
+        }
+        vm.prank(ALICE_ADDRESS);
+        vm.expectRevert(abi.encodeWithSelector(ProofHeir.InvalidPublicInputsLength.selector, 149, 148));
+        ProofHeir(BOB_ADDRESS).proveDeathAndRegisterHeir(proof, padded);
+    }
 }
@@ -146,10 +146,11 @@ contract VerifierIntegrationTest is Test {
 
     /**
      * @dev Helper to build public inputs array from Prover.toml values.
-     *      Total: 116 fields (20 recipient + 32 server_hash + 32 id_commitment + 32 status_commitment)
+     *      Total: 148 fields (20 recipient + 32 server_hash + 32 id_commitment + 32 status_commitment
+     *      + 32 nuip_commitment)
      */
     function _buildPublicInputs() internal pure returns (bytes32[] memory) {
-        bytes32[] memory publicInputs = new bytes32[](116);
+        bytes32[] memory publicInputs = new bytes32[](148);
         
         // Recipient: 0x03f72d5859858AFF7b93096B4AD9593442DD2327
         uint8[20] memory recipientBytes = [
@@ -186,6 +187,15 @@ contract VerifierIntegrationTest is Test {
         for (uint i = 0; i < 32; i++) {
             publicInputs[84 + i] = bytes32(uint256(statusCommitmentBytes[i]));
         }
+
+        // NUIP Commitment: sha256(nuip || nuip_blinder) from Prover.toml = 0xa19bce84589966b9f60f...
+        uint8[32] memory nuipCommitmentBytes = [
+            0xa1, 0x9b, 0xce, 0x84, 0x58, 0x99, 0x66, 0xb9, 0xf6, 0x0f, 0x33, 0x07, 0xe1, 0x93, 0x0f, 0x80,
+            0x28, 0x0c, 0xd3, 0x28, 0x82, 0xbb, 0xd4, 0xe7, 0xb3, 0x1a, 0x1d, 0xc8, 0x9f, 0x73, 0x03, 0x45
+        ];
+        for (uint i = 0; i < 32; i++) {
+            publicInputs[116 + i] = bytes32(uint256(nuipCommitmentBytes[i]));
+        }
         
         return publicInputs;
     }
@@ -0,0 +1,309 @@
+//! Mock Colombian civil registry for offline tests
+//!
+//! Serves the same endpoints and JSON shapes as the Railway deployment over HTTPS
+//! with a certificate issued by a freshly generated CA:
+//!
+//! * `POST /VigenciaCedula/consulta` `{ "nuip": number, "ip": string }` → `{ "nuip", "vigencia" }`
+//! * `POST /admin/update-status` `{ "nuip": number, "status": string }` → `{ "nuip", "status" }`
+//! * `POST /admin/fault` `{ "fault": "none" | "not_found" | "server_error" | "malformed_json" | "slow", "delay_ms"? }`
+//!
+//! Faults only affect the status query, so tests can still reconfigure the registry.
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, KeyPair};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Status the registry reports for a deceased person
+pub const DECEASED: &str = "No Vigente (Fallecido)";
+/// Status the registry reports for a living person
+pub const ALIVE: &str = "Vigente (Vivo)";
+
+/// Path of the status query endpoint
+pub const STATUS_PATH: &str = "/VigenciaCedula/consulta";
+
+/// Failure injected into the status query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "fault", rename_all = "snake_case")]
+pub enum Fault {
+    /// Answer normally
+    #[default]
+    None,
+    /// 404 for every NUIP
+    NotFound,
+    /// 500 Internal Server Error
+    ServerError,
+    /// 200 with a truncated JSON body
+    MalformedJson,
+    /// Answer normally after a delay
+    Slow { delay_ms: u64 },
+}
+
+/// Records and fault configuration shared by all handlers
+#[derive(Debug, Default)]
+struct RegistryState {
+    records: HashMap<u64, String>,
+    fault: Fault,
+}
+
+type SharedState = Arc<RwLock<RegistryState>>;
+
+/// Configuration of a [`MockRegistry`]
+#[derive(Debug, Clone)]
+pub struct MockRegistryConfig {
+    /// Address to bind; port 0 picks a free port
+    pub bind: SocketAddr,
+    /// Hostnames on the generated certificate; the first one is used in [`MockRegistry::url`]
+    pub hostnames: Vec<String>,
+    /// Initial records, NUIP → vigencia
+    pub records: HashMap<u64, String>,
+    /// Initial fault
+    pub fault: Fault,
+}
+
+impl Default for MockRegistryConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            hostnames: vec!["localhost".to_string()],
+            records: HashMap::new(),
+            fault: Fault::None,
+        }
+    }
+}
+
+impl MockRegistryConfig {
+    /// Add a record
+    pub fn with_record(mut self, nuip: u64, vigencia: &str) -> Self {
+        self.records.insert(nuip, vigencia.to_string());
+        self
+    }
+}
+
+/// A running mock registry
+pub struct MockRegistry {
+    addr: SocketAddr,
+    hostname: String,
+    ca_pem: String,
+    cert_der: Vec<u8>,
+    state: SharedState,
+    handle: Handle,
+}
+
+impl MockRegistry {
+    /// Generate a certificate, bind and start serving in the background
+    pub async fn start(config: MockRegistryConfig) -> io::Result<Self> {
+        let hostname = config
+            .hostnames
+            .first()
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no hostname"))?;
+
+        let (ca_pem, cert_der, key_der) =
+            issue_certificate(config.hostnames.clone()).map_err(io::Error::other)?;
+        let tls = tls_config(cert_der.clone(), key_der)?;
+
+        let state: SharedState = Arc::new(RwLock::new(RegistryState {
+            records: config.records,
+            fault: config.fault,
+        }));
+
+        let listener = TcpListener::bind(config.bind)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let handle = Handle::new();
+        let server = axum_server::from_tcp_rustls(listener, RustlsConfig::from_config(Arc::new(tls)))
+            .handle(handle.clone())
+            .serve(router(state.clone()).into_make_service());
+
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                tracing::error!("Mock registry stopped: {}", e);
+            }
+        });
+
+        tracing::info!("Mock registry listening on https://{}:{}", hostname, addr.port());
+
+        Ok(Self {
+            addr,
+            hostname,
+            ca_pem,
+            cert_der,
+            state,
+            handle,
+        })
+    }
+
+    /// Bound socket address
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Full URL of the status query endpoint (`CIVIL_REGISTRY_URL`)
+    pub fn url(&self) -> String {
+        format!("https://{}:{}{}", self.hostname, self.addr.port(), STATUS_PATH)
+    }
+
+    /// Generated CA certificate in PEM, to be used as a CA bundle by clients
+    pub fn ca_pem(&self) -> &str {
+        &self.ca_pem
+    }
+
+    /// Server (leaf) certificate in DER, e.g. to compute an SPKI pin
+    pub fn cert_der(&self) -> &[u8] {
+        &self.cert_der
+    }
+
+    /// Insert or update a record
+    pub fn set_status(&self, nuip: u64, vigencia: &str) {
+        self.state
+            .write()
+            .expect("registry state poisoned")
+            .records
+            .insert(nuip, vigencia.to_string());
+    }
+
+    /// Change the injected fault
+    pub fn set_fault(&self, fault: Fault) {
+        self.state.write().expect("registry state poisoned").fault = fault;
+    }
+
+    /// Stop accepting connections
+    pub fn shutdown(&self) {
+        self.handle.shutdown();
+    }
+}
+
+impl Drop for MockRegistry {
+    fn drop(&mut self) {
+        self.handle.shutdown();
+    }
+}
+
+/// Generate a CA and a server certificate for `hostnames` signed by it
+///
+/// Returns the CA in PEM and the server certificate and PKCS#8 key in DER.
+fn issue_certificate(hostnames: Vec<String>) -> Result<(String, Vec<u8>, Vec<u8>), rcgen::Error> {
+    let ca_key = KeyPair::generate()?;
+    let mut ca_params = CertificateParams::new(Vec::<String>::new())?;
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "ProofHeir Mock Registry CA");
+    let ca_cert = ca_params.self_signed(&ca_key)?;
+
+    let server_key = KeyPair::generate()?;
+    let server_cert = CertificateParams::new(hostnames)?.signed_by(&server_key, &ca_cert, &ca_key)?;
+
+    Ok((ca_cert.pem(), server_cert.der().to_vec(), server_key.serialize_der()))
+}
+
+/// TLS 1.2 only: MPC-TLS cannot negotiate TLS 1.3
+fn tls_config(cert_der: Vec<u8>, key_der: Vec<u8>) -> io::Result<rustls::ServerConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_protocol_versions(&[&rustls::version::TLS12])
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![CertificateDer::from(cert_der)],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_der)),
+        )
+        .map_err(io::Error::other)?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Routes of the mock registry
+fn router(state: SharedState) -> Router {
+    Router::new()
+        .route(STATUS_PATH, post(consulta))
+        .route("/admin/update-status", post(update_status))
+        .route("/admin/fault", post(set_fault))
+        .with_state(state)
+}
+
+/// The registry accepts the NUIP as a JSON number; strings are tolerated
+fn parse_nuip(body: &Value) -> Option<u64> {
+    match body.get("nuip")? {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn error(status: StatusCode, detail: &str) -> Response {
+    (status, Json(json!({ "detail": detail }))).into_response()
+}
+
+/// POST /VigenciaCedula/consulta
+async fn consulta(State(state): State<SharedState>, Json(body): Json<Value>) -> Response {
+    let fault = state.read().expect("registry state poisoned").fault;
+
+    match fault {
+        Fault::None => {}
+        Fault::NotFound => return error(StatusCode::NOT_FOUND, "Not Found"),
+        Fault::ServerError => return error(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error"),
+        Fault::MalformedJson => {
+            return (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, "application/json")],
+                r#"{"nuip": 0, "vigencia": "No Vigente"#,
+            )
+                .into_response()
+        }
+        Fault::Slow { delay_ms } => tokio::time::sleep(Duration::from_millis(delay_ms)).await,
+    }
+
+    let Some(nuip) = parse_nuip(&body) else {
+        return error(StatusCode::UNPROCESSABLE_ENTITY, "nuip must be a number");
+    };
+
+    let vigencia = state
+        .read()
+        .expect("registry state poisoned")
+        .records
+        .get(&nuip)
+        .cloned();
+
+    match vigencia {
+        Some(vigencia) => Json(json!({ "nuip": nuip, "vigencia": vigencia })).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Person not found"),
+    }
+}
+
+/// POST /admin/update-status
+async fn update_status(State(state): State<SharedState>, Json(body): Json<Value>) -> Response {
+    let (Some(nuip), Some(status)) = (parse_nuip(&body), body.get("status").and_then(|s| s.as_str()))
+    else {
+        return error(StatusCode::UNPROCESSABLE_ENTITY, "nuip and status are required");
+    };
+
+    state
+        .write()
+        .expect("registry state poisoned")
+        .records
+        .insert(nuip, status.to_string());
+
+    Json(json!({ "nuip": nuip, "status": status })).into_response()
+}
+
+/// POST /admin/fault
+async fn set_fault(State(state): State<SharedState>, Json(fault): Json<Fault>) -> Json<Fault> {
+    state.write().expect("registry state poisoned").fault = fault;
+    Json(fault)
+}
@@ -0,0 +1,78 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+use mock_registry::{Fault, MockRegistry, MockRegistryConfig, ALIVE, DECEASED};
+
+/// Mock Colombian civil registry over HTTPS (certificate from a generated CA)
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8443")]
+    bind: SocketAddr,
+
+    /// Hostname on the certificate (repeatable); the first one is used in the printed URL
+    #[arg(long = "hostname", default_value = "localhost")]
+    hostnames: Vec<String>,
+
+    /// NUIP registered as deceased (repeatable)
+    #[arg(long, default_value = "454545454")]
+    deceased: Vec<u64>,
+
+    /// NUIP registered as alive (repeatable)
+    #[arg(long)]
+    alive: Vec<u64>,
+
+    /// Write the CA certificate (PEM) here, for TLS_CA_BUNDLES
+    #[arg(long, default_value = "mock-registry.pem")]
+    cert_out: String,
+
+    /// Fault injected in status queries: none, not_found, server_error, malformed_json
+    #[arg(long, default_value = "none")]
+    fault: String,
+
+    /// Delay in milliseconds before answering status queries
+    #[arg(long)]
+    delay_ms: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+
+    let fault = match (args.fault.as_str(), args.delay_ms) {
+        ("none", Some(delay_ms)) => Fault::Slow { delay_ms },
+        ("none", None) => Fault::None,
+        ("not_found", _) => Fault::NotFound,
+        ("server_error", _) => Fault::ServerError,
+        ("malformed_json", _) => Fault::MalformedJson,
+        (other, _) => return Err(format!("Unknown fault: {}", other).into()),
+    };
+
+    let mut config = MockRegistryConfig {
+        bind: args.bind,
+        hostnames: args.hostnames,
+        fault,
+        ..MockRegistryConfig::default()
+    };
+    for nuip in args.deceased {
+        config = config.with_record(nuip, DECEASED);
+    }
+    for nuip in args.alive {
+        config = config.with_record(nuip, ALIVE);
+    }
+
+    let registry = MockRegistry::start(config).await?;
+    std::fs::write(&args.cert_out, registry.ca_pem())?;
+
+    println!("Mock registry ready");
+    println!("   CIVIL_REGISTRY_URL={}", registry.url());
+    println!("   TLS_CA_BUNDLES={}", args.cert_out);
+
+    tokio::signal::ctrl_c().await?;
+    registry.shutdown();
+
+    Ok(())
+}
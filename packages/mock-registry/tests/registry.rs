@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use mock_registry::{Fault, MockRegistry, MockRegistryConfig, ALIVE, DECEASED};
+use serde_json::{json, Value};
+
+const NUIP: u64 = 454545454;
+
+async fn start() -> (MockRegistry, reqwest::Client) {
+    let registry = MockRegistry::start(MockRegistryConfig::default().with_record(NUIP, DECEASED))
+        .await
+        .unwrap();
+
+    // Trust only the registry's generated CA
+    let client = reqwest::Client::builder()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(reqwest::Certificate::from_pem(registry.ca_pem().as_bytes()).unwrap())
+        .resolve("localhost", registry.addr())
+        .build()
+        .unwrap();
+
+    (registry, client)
+}
+
+async fn consulta(registry: &MockRegistry, client: &reqwest::Client, nuip: u64) -> reqwest::Response {
+    client
+        .post(registry.url())
+        .json(&json!({ "nuip": nuip, "ip": "143.137.96.53" }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_status_query_and_update() {
+    let (registry, client) = start().await;
+
+    let res = consulta(&registry, &client, NUIP).await;
+    assert_eq!(res.status(), 200);
+    let body: Value = res.json().await.unwrap();
+    assert_eq!(body, json!({ "nuip": NUIP, "vigencia": DECEASED }));
+
+    assert_eq!(consulta(&registry, &client, 1).await.status(), 404);
+
+    let admin_url = registry.url().replace("/VigenciaCedula/consulta", "/admin/update-status");
+    let res = client
+        .post(admin_url)
+        .json(&json!({ "nuip": 1, "status": ALIVE }))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = res.json().await.unwrap();
+    assert_eq!(body, json!({ "nuip": 1, "status": ALIVE }));
+
+    let body: Value = consulta(&registry, &client, 1).await.json().await.unwrap();
+    assert_eq!(body["vigencia"], ALIVE);
+}
+
+#[tokio::test]
+async fn test_injected_faults() {
+    let (registry, client) = start().await;
+
+    registry.set_fault(Fault::NotFound);
+    assert_eq!(consulta(&registry, &client, NUIP).await.status(), 404);
+
+    registry.set_fault(Fault::ServerError);
+    assert_eq!(consulta(&registry, &client, NUIP).await.status(), 500);
+
+    registry.set_fault(Fault::MalformedJson);
+    let res = consulta(&registry, &client, NUIP).await;
+    assert_eq!(res.status(), 200);
+    assert!(res.json::<Value>().await.is_err());
+
+    // Faults can also be set over HTTP
+    let fault_url = registry.url().replace("/VigenciaCedula/consulta", "/admin/fault");
+    client
+        .post(fault_url)
+        .json(&json!({ "fault": "slow", "delay_ms": 300 }))
+        .send()
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    assert_eq!(consulta(&registry, &client, NUIP).await.status(), 200);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
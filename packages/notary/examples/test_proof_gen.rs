@@ -1,5 +1,6 @@
 /// Example to test proof_gen module directly
 use notary::proof_gen::generate_death_proof;
+use notary::{EthAddress, Nuip, Salt};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -9,19 +10,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🧪 Testing proof_gen module...\n");
 
     // Test parameters (same as main.rs test)
-    let recipient = [0xab; 20]; // abababababababababababababababababababab
-    let nuip = "454545454".to_string();
-    let salt = [0x11; 32]; // 1111...1111
+    let recipient = EthAddress::from([0xab; 20]); // abababababababababababababababababababab
+    let nuip: Nuip = "454545454".parse()?;
+    let salt = Salt::from([0x11; 32]); // 1111...1111
+    let testator_address = EthAddress::from([0x00; 20]); // delegated account receiving the transaction
 
     println!("📋 Test Parameters:");
-    println!("   Recipient: 0x{}", hex::encode(&recipient));
-    println!("   NUIP: {}", nuip);
-    println!("   Salt: 0x{}\n", hex::encode(&salt));
+    println!("   Recipient: {}", recipient);
+    println!("   NUIP: {}", nuip.as_str());
+    println!("   Salt: 0x{}", hex::encode(salt.as_bytes()));
+    println!("   Testator: {}\n", testator_address);
 
     // Call the proof generation function
     println!("🚀 Calling generate_death_proof()...\n");
     
-    let result = generate_death_proof(recipient, nuip, salt).await?;
+    let result = generate_death_proof(recipient, nuip, salt, testator_address).await?;
 
     println!("\n✅ Proof generation successful!\n");
     println!("📦 Results:");
@@ -29,10 +32,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   VK size: {} bytes", result.vk.len());
     println!("   Public inputs: {} fields", result.public_inputs.len());
     println!("   First public input: {}", result.public_inputs[0]);
-    println!("   Last public input: {}", result.public_inputs[115]);
+    println!("   Last public input: {}", result.public_inputs[147]);
 
-    // Verify we have exactly 116 fields
-    assert_eq!(result.public_inputs.len(), 116, "Must have exactly 116 public input fields");
+    // Verify we have exactly 148 fields
+    assert_eq!(result.public_inputs.len(), 148, "Must have exactly 148 public input fields");
     println!("\n✅ All assertions passed!");
 
     Ok(())
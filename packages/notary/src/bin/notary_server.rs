@@ -0,0 +1,46 @@
+use clap::Parser;
+use notary::remote::{serve, ServerOptions, DEFAULT_NOTARY_ADDR};
+use notary::verifier::VerifierOptions;
+use notary::{CircuitContext, RegistryAdapter};
+use tokio::net::TcpListener;
+
+/// Standalone ProofHeir notary: runs the verifier side of MPC-TLS for remote provers
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Address to listen on for prover connections
+    #[arg(short, long, default_value = DEFAULT_NOTARY_ADDR)]
+    listen: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+
+    // Registry endpoint, allowlist and chain settings come from the environment
+    let options = VerifierOptions::from_env();
+    tracing::info!("Expecting registry endpoint {}", options.registry.url());
+    if !options.tls.spki_pins.is_empty() {
+        tracing::warn!("TLS_SPKI_PINS is ignored by the notary: only the prover sees the certificate");
+    }
+
+    // Submission modes provers may ask for, and how many sessions run at once
+    let server = ServerOptions::from_env()?;
+    tracing::info!(
+        "Allowing submission modes {:?}, {} sessions at a time",
+        server.allowed_submission,
+        server.max_sessions
+    );
+
+    // Bytecode, SRS and verification key once, checked against CIRCUIT_VK_HASH if set
+    tokio::task::spawn_blocking(CircuitContext::global).await??;
+
+    let listener = TcpListener::bind(&args.listen).await?;
+    tracing::info!("🛡️ Notary listening on {}", listener.local_addr()?);
+
+    serve(listener, options, server).await?;
+
+    Ok(())
+}
@@ -0,0 +1,633 @@
+/// Chain access - everything the notary needs from the ProofHeir delegated accounts
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use alloy::{
+    network::EthereumWallet,
+    primitives::{keccak256, Address, Bytes, B256},
+    providers::ProviderBuilder,
+    signers::local::PrivateKeySigner,
+    sol,
+    sol_types::{SolCall, SolInterface},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::error::{NotaryError, Result};
+
+// ProofHeir ABI (packages/contracts/src/ProofHeir.sol), called through a testator's EIP-7702 delegated account
+sol! {
+    #[sol(rpc)]
+    contract ProofHeir {
+        function verifier() external view returns (address);
+        function trustedServerHash() external view returns (bytes32);
+
+        function registerIdentity(bytes32 _identityCommitment) external;
+        function proveDeathAndRegisterHeir(bytes calldata proof, bytes32[] calldata publicInputs) external;
+        function claimInheritance(address[] calldata tokens) external;
+        function getIdentityCommitment() external view returns (bytes32);
+        function getRegisteredHeir() external view returns (address);
+
+        event AssetsClaimed(address indexed caller, address indexed heir, address indexed token, uint256 amount);
+        event IdentityRegistered(address indexed owner, bytes32 commitment);
+        event HeirRegistered(address indexed owner, address indexed heir);
+
+        error IdentityNotRegistered();
+        error IdentityAlreadyRegistered();
+        error InvalidPublicInputsLength(uint256 provided, uint256 expected);
+        error ProofIdentityMismatch(bytes32 proofCommitment, bytes32 registeredCommitment);
+        error InvalidDataSource(bytes32 proofServerHash, bytes32 trustedHash);
+        error InvalidZKProof();
+        error NoHeirRegistered();
+        error NotOwner();
+        error NotTrustedVerifier();
+    }
+}
+
+/// A ProofHeir custom error decoded from revert data
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProofHeirError {
+    #[error("the testator has not registered an identity commitment")]
+    IdentityNotRegistered,
+    #[error("an identity commitment is already registered")]
+    IdentityAlreadyRegistered,
+    #[error("{provided} public input fields sent, the contract requires {expected}")]
+    InvalidPublicInputsLength { provided: u64, expected: u64 },
+    #[error(
+        "proof id_commitment 0x{} does not match the registered commitment 0x{}",
+        hex::encode(.proof_commitment),
+        hex::encode(.registered_commitment)
+    )]
+    ProofIdentityMismatch {
+        proof_commitment: [u8; 32],
+        registered_commitment: [u8; 32],
+    },
+    #[error(
+        "proof server hash 0x{} is not the contract's trusted server hash 0x{}",
+        hex::encode(.proof_server_hash),
+        hex::encode(.trusted_hash)
+    )]
+    InvalidDataSource {
+        proof_server_hash: [u8; 32],
+        trusted_hash: [u8; 32],
+    },
+    #[error("the on-chain verifier rejected the ZK proof")]
+    InvalidZkProof,
+    #[error("no heir is registered")]
+    NoHeirRegistered,
+    #[error("caller is not the delegated account")]
+    NotOwner,
+    #[error("caller is not the trusted verifier")]
+    NotTrustedVerifier,
+}
+
+impl ProofHeirError {
+    /// Decode ABI-encoded revert data; `None` if it is not a ProofHeir error
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        use ProofHeir::ProofHeirErrors as E;
+
+        Some(match E::abi_decode(data, true).ok()? {
+            E::IdentityNotRegistered(_) => Self::IdentityNotRegistered,
+            E::IdentityAlreadyRegistered(_) => Self::IdentityAlreadyRegistered,
+            E::InvalidPublicInputsLength(e) => Self::InvalidPublicInputsLength {
+                provided: e.provided.saturating_to(),
+                expected: e.expected.saturating_to(),
+            },
+            E::ProofIdentityMismatch(e) => Self::ProofIdentityMismatch {
+                proof_commitment: e.proofCommitment.0,
+                registered_commitment: e.registeredCommitment.0,
+            },
+            E::InvalidDataSource(e) => Self::InvalidDataSource {
+                proof_server_hash: e.proofServerHash.0,
+                trusted_hash: e.trustedHash.0,
+            },
+            E::InvalidZKProof(_) => Self::InvalidZkProof,
+            E::NoHeirRegistered(_) => Self::NoHeirRegistered,
+            E::NotOwner(_) => Self::NotOwner,
+            E::NotTrustedVerifier(_) => Self::NotTrustedVerifier,
+        })
+    }
+
+    /// Solidity name of the error
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IdentityNotRegistered => "IdentityNotRegistered",
+            Self::IdentityAlreadyRegistered => "IdentityAlreadyRegistered",
+            Self::InvalidPublicInputsLength { .. } => "InvalidPublicInputsLength",
+            Self::ProofIdentityMismatch { .. } => "ProofIdentityMismatch",
+            Self::InvalidDataSource { .. } => "InvalidDataSource",
+            Self::InvalidZkProof => "InvalidZKProof",
+            Self::NoHeirRegistered => "NoHeirRegistered",
+            Self::NotOwner => "NotOwner",
+            Self::NotTrustedVerifier => "NotTrustedVerifier",
+        }
+    }
+}
+
+/// Default JSON-RPC endpoint (local anvil)
+pub const DEFAULT_RPC_URL: &str = "http://localhost:8545";
+
+/// How long to wait for a receipt before reporting the transaction as dropped
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A mined, successful transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxReceipt {
+    pub tx_hash: B256,
+    pub block_number: u64,
+    pub gas_used: u64,
+}
+
+/// What happened to a `proveDeathAndRegisterHeir` submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxOutcome {
+    /// Mined with status 1
+    Confirmed(TxReceipt),
+    /// Rejected by the contract, either while estimating gas (no hash, revert data
+    /// available) or after being mined with status 0
+    Reverted {
+        tx_hash: Option<B256>,
+        data: Option<Bytes>,
+    },
+    /// Sent but never confirmed
+    Dropped { tx_hash: B256, reason: String },
+}
+
+/// Result of an `eth_call` simulation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationOutcome {
+    /// The call would succeed
+    Success,
+    /// The call would revert, with the revert data when the node reports it
+    Reverted { data: Option<Bytes> },
+}
+
+/// Access to the chain where the testators' delegated accounts live
+#[async_trait]
+pub trait ChainClient: Send + Sync {
+    /// Call `proveDeathAndRegisterHeir` on `account` and wait for the outcome
+    async fn prove_death_and_register_heir(
+        &self,
+        account: Address,
+        proof: Bytes,
+        public_inputs: Vec<B256>,
+    ) -> Result<TxOutcome>;
+
+    /// Run `proveDeathAndRegisterHeir` on `account` with `eth_call`, without sending anything
+    async fn simulate_prove_death_and_register_heir(
+        &self,
+        account: Address,
+        proof: Bytes,
+        public_inputs: Vec<B256>,
+    ) -> Result<SimulationOutcome>;
+
+    /// `getIdentityCommitment()` of `account` (zero when not registered)
+    async fn get_identity_commitment(&self, account: Address) -> Result<B256>;
+
+    /// `trustedServerHash()` of the ProofHeir implementation `account` delegates to
+    async fn trusted_server_hash(&self, account: Address) -> Result<B256>;
+}
+
+/// [`ChainClient`] over JSON-RPC with alloy, signing with the verifier's key
+#[derive(Debug, Clone)]
+pub struct AlloyChain {
+    rpc_url: String,
+    private_key: Option<String>,
+}
+
+impl AlloyChain {
+    pub fn new(rpc_url: impl Into<String>, private_key: Option<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            private_key,
+        }
+    }
+
+    /// Reads `RPC_URL` (default local anvil) and `VERIFIER_PRIVATE_KEY`
+    ///
+    /// A missing key only fails when a transaction is sent.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string()),
+            std::env::var("VERIFIER_PRIVATE_KEY").ok(),
+        )
+    }
+}
+
+#[async_trait]
+impl ChainClient for AlloyChain {
+    async fn prove_death_and_register_heir(
+        &self,
+        account: Address,
+        proof: Bytes,
+        public_inputs: Vec<B256>,
+    ) -> Result<TxOutcome> {
+        let private_key = self
+            .private_key
+            .as_deref()
+            .ok_or_else(|| NotaryError::on_chain("VERIFIER_PRIVATE_KEY not set in environment"))?;
+
+        tracing::info!("🔗 RPC URL: {}", self.rpc_url);
+
+        // Create signer from private key
+        let signer: PrivateKeySigner = private_key
+            .parse()
+            .map_err(|e| NotaryError::on_chain(format!("Invalid private key: {}", e)))?;
+        let wallet = EthereumWallet::from(signer);
+
+        tracing::info!(
+            "🔑 Using verifier wallet: {}",
+            wallet.default_signer().address()
+        );
+
+        // Create provider with signer
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet)
+            .on_http(
+                self.rpc_url
+                    .parse()
+                    .map_err(|e| NotaryError::on_chain(format!("Invalid RPC URL: {}", e)))?,
+            );
+
+        // Call the function on the testator's delegated account, not on the ProofHeir implementation
+        let contract = ProofHeir::new(account, &provider);
+
+        // Let the provider estimate gas (required for Mantle's L1+L2 cost calculation)
+        let pending_tx = match contract
+            .proveDeathAndRegisterHeir(proof, public_inputs)
+            .send()
+            .await
+        {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                // Custom errors surface during gas estimation
+                if let Some(data) = revert_data(&e) {
+                    return Ok(TxOutcome::Reverted {
+                        tx_hash: None,
+                        data: Some(data),
+                    });
+                }
+                return Err(NotaryError::on_chain(format!(
+                    "Failed to send transaction: {}",
+                    e
+                )));
+            }
+        };
+
+        let tx_hash = *pending_tx.tx_hash();
+        tracing::info!("📝 Transaction sent! Hash: {:?}", tx_hash);
+        tracing::info!("⏳ Waiting for transaction confirmation...");
+
+        match pending_tx
+            .with_timeout(Some(RECEIPT_TIMEOUT))
+            .get_receipt()
+            .await
+        {
+            Ok(receipt) if receipt.status() => Ok(TxOutcome::Confirmed(TxReceipt {
+                tx_hash,
+                block_number: receipt.block_number.unwrap_or(0),
+                gas_used: receipt.gas_used,
+            })),
+            Ok(_) => Ok(TxOutcome::Reverted {
+                tx_hash: Some(tx_hash),
+                data: None,
+            }),
+            Err(e) => Ok(TxOutcome::Dropped {
+                tx_hash,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    async fn simulate_prove_death_and_register_heir(
+        &self,
+        account: Address,
+        proof: Bytes,
+        public_inputs: Vec<B256>,
+    ) -> Result<SimulationOutcome> {
+        let provider = ProviderBuilder::new().on_http(
+            self.rpc_url
+                .parse()
+                .map_err(|e| NotaryError::on_chain(format!("Invalid RPC URL: {}", e)))?,
+        );
+
+        let contract = ProofHeir::new(account, &provider);
+        let mut call = contract.proveDeathAndRegisterHeir(proof, public_inputs);
+
+        // Simulate as the verifier wallet when it is configured
+        if let Some(private_key) = &self.private_key {
+            let signer: PrivateKeySigner = private_key
+                .parse()
+                .map_err(|e| NotaryError::on_chain(format!("Invalid private key: {}", e)))?;
+            call = call.from(signer.address());
+        }
+
+        match call.call().await {
+            Ok(_) => Ok(SimulationOutcome::Success),
+            Err(e) => {
+                if let Some(data) = revert_data(&e) {
+                    return Ok(SimulationOutcome::Reverted { data: Some(data) });
+                }
+                // Bare `revert()`: the node reports the revert without data
+                let reverted = matches!(
+                    &e,
+                    alloy::contract::Error::TransportError(e)
+                        if e.as_error_resp().is_some_and(|payload| payload.message.contains("revert"))
+                );
+                if reverted {
+                    return Ok(SimulationOutcome::Reverted { data: None });
+                }
+                Err(NotaryError::on_chain(format!(
+                    "eth_call simulation failed: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    async fn get_identity_commitment(&self, account: Address) -> Result<B256> {
+        let provider = ProviderBuilder::new().on_http(
+            self.rpc_url
+                .parse()
+                .map_err(|e| NotaryError::on_chain(format!("Invalid RPC URL: {}", e)))?,
+        );
+
+        let commitment = ProofHeir::new(account, &provider)
+            .getIdentityCommitment()
+            .call()
+            .await
+            .map_err(|e| NotaryError::on_chain(format!("getIdentityCommitment failed: {}", e)))?;
+
+        Ok(commitment._0)
+    }
+
+    async fn trusted_server_hash(&self, account: Address) -> Result<B256> {
+        let provider = ProviderBuilder::new().on_http(
+            self.rpc_url
+                .parse()
+                .map_err(|e| NotaryError::on_chain(format!("Invalid RPC URL: {}", e)))?,
+        );
+
+        // Immutable of the implementation, readable through the delegated account
+        let server_hash = ProofHeir::new(account, &provider)
+            .trustedServerHash()
+            .call()
+            .await
+            .map_err(|e| NotaryError::on_chain(format!("trustedServerHash failed: {}", e)))?;
+
+        Ok(server_hash._0)
+    }
+}
+
+/// Revert data carried by a JSON-RPC error response, if any
+fn revert_data(error: &alloy::contract::Error) -> Option<Bytes> {
+    match error {
+        alloy::contract::Error::TransportError(e) => e
+            .as_error_resp()
+            .and_then(|payload| payload.as_revert_data()),
+        _ => None,
+    }
+}
+
+/// A `proveDeathAndRegisterHeir` call recorded by [`MockChain`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCall {
+    pub account: Address,
+    pub proof: Bytes,
+    pub public_inputs: Vec<B256>,
+}
+
+/// How [`MockChain`] answers submissions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockOutcome {
+    /// Mined successfully
+    Success,
+    /// Reverted during gas estimation with this revert data (e.g. an ABI-encoded ProofHeir error)
+    Revert(Bytes),
+    /// Mined with status 0
+    RevertedOnChain,
+    /// Never confirmed
+    Dropped,
+}
+
+/// In-memory [`ChainClient`] for tests
+///
+/// Records every `proveDeathAndRegisterHeir` call and answers with the configured outcome.
+#[derive(Debug)]
+pub struct MockChain {
+    outcome: Mutex<MockOutcome>,
+    identity_commitments: Mutex<HashMap<Address, B256>>,
+    trusted_server_hash: Mutex<B256>,
+    calls: Mutex<Vec<RecordedCall>>,
+    simulations: Mutex<Vec<RecordedCall>>,
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        Self::new(MockOutcome::Success)
+    }
+}
+
+impl MockChain {
+    /// Block number reported in successful receipts
+    pub const BLOCK_NUMBER: u64 = 1;
+    /// Gas used reported in successful receipts
+    pub const GAS_USED: u64 = 350_000;
+
+    pub fn new(outcome: MockOutcome) -> Self {
+        Self {
+            outcome: Mutex::new(outcome),
+            identity_commitments: Mutex::default(),
+            trusted_server_hash: Mutex::default(),
+            calls: Mutex::default(),
+            simulations: Mutex::default(),
+        }
+    }
+
+    /// Change the outcome of the next submissions
+    pub fn set_outcome(&self, outcome: MockOutcome) {
+        *self.outcome.lock().expect("mock chain poisoned") = outcome;
+    }
+
+    /// Simulate `registerIdentity` on `account`
+    pub fn register_identity(&self, account: Address, commitment: B256) {
+        self.identity_commitments
+            .lock()
+            .expect("mock chain poisoned")
+            .insert(account, commitment);
+    }
+
+    /// Set the `trustedServerHash` every account reports (zero by default)
+    pub fn set_trusted_server_hash(&self, server_hash: B256) {
+        *self
+            .trusted_server_hash
+            .lock()
+            .expect("mock chain poisoned") = server_hash;
+    }
+
+    /// Submissions received so far
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.calls.lock().expect("mock chain poisoned").clone()
+    }
+
+    /// `eth_call` simulations received so far
+    pub fn simulations(&self) -> Vec<RecordedCall> {
+        self.simulations
+            .lock()
+            .expect("mock chain poisoned")
+            .clone()
+    }
+}
+
+#[async_trait]
+impl ChainClient for MockChain {
+    async fn prove_death_and_register_heir(
+        &self,
+        account: Address,
+        proof: Bytes,
+        public_inputs: Vec<B256>,
+    ) -> Result<TxOutcome> {
+        let call = ProofHeir::proveDeathAndRegisterHeirCall {
+            proof: proof.clone(),
+            publicInputs: public_inputs.clone(),
+        };
+        let tx_hash = keccak256([account.as_slice(), &call.abi_encode()].concat());
+
+        self.calls
+            .lock()
+            .expect("mock chain poisoned")
+            .push(RecordedCall {
+                account,
+                proof,
+                public_inputs,
+            });
+
+        let outcome = self.outcome.lock().expect("mock chain poisoned").clone();
+        Ok(match outcome {
+            MockOutcome::Success => TxOutcome::Confirmed(TxReceipt {
+                tx_hash,
+                block_number: Self::BLOCK_NUMBER,
+                gas_used: Self::GAS_USED,
+            }),
+            MockOutcome::Revert(data) => TxOutcome::Reverted {
+                tx_hash: None,
+                data: Some(data),
+            },
+            MockOutcome::RevertedOnChain => TxOutcome::Reverted {
+                tx_hash: Some(tx_hash),
+                data: None,
+            },
+            MockOutcome::Dropped => TxOutcome::Dropped {
+                tx_hash,
+                reason: "transaction not mined".to_string(),
+            },
+        })
+    }
+
+    async fn simulate_prove_death_and_register_heir(
+        &self,
+        account: Address,
+        proof: Bytes,
+        public_inputs: Vec<B256>,
+    ) -> Result<SimulationOutcome> {
+        self.simulations
+            .lock()
+            .expect("mock chain poisoned")
+            .push(RecordedCall {
+                account,
+                proof,
+                public_inputs,
+            });
+
+        // Only reverts that gas estimation would see show up in a simulation
+        let outcome = self.outcome.lock().expect("mock chain poisoned").clone();
+        Ok(match outcome {
+            MockOutcome::Revert(data) => SimulationOutcome::Reverted { data: Some(data) },
+            MockOutcome::Success | MockOutcome::RevertedOnChain | MockOutcome::Dropped => {
+                SimulationOutcome::Success
+            }
+        })
+    }
+
+    async fn get_identity_commitment(&self, account: Address) -> Result<B256> {
+        Ok(self
+            .identity_commitments
+            .lock()
+            .expect("mock chain poisoned")
+            .get(&account)
+            .copied()
+            .unwrap_or(B256::ZERO))
+    }
+
+    async fn trusted_server_hash(&self, _account: Address) -> Result<B256> {
+        Ok(*self
+            .trusted_server_hash
+            .lock()
+            .expect("mock chain poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+    use alloy::sol_types::SolError;
+
+    #[test]
+    fn test_decode_proof_heir_errors() {
+        let data = ProofHeir::IdentityNotRegistered {}.abi_encode();
+        assert_eq!(
+            ProofHeirError::decode(&data),
+            Some(ProofHeirError::IdentityNotRegistered)
+        );
+
+        let data = ProofHeir::InvalidPublicInputsLength {
+            provided: U256::from(20),
+            expected: U256::from(148),
+        }
+        .abi_encode();
+        assert_eq!(
+            ProofHeirError::decode(&data),
+            Some(ProofHeirError::InvalidPublicInputsLength {
+                provided: 20,
+                expected: 148
+            })
+        );
+
+        let data = ProofHeir::ProofIdentityMismatch {
+            proofCommitment: B256::repeat_byte(1),
+            registeredCommitment: B256::repeat_byte(2),
+        }
+        .abi_encode();
+        let error = ProofHeirError::decode(&data).unwrap();
+        assert_eq!(
+            error,
+            ProofHeirError::ProofIdentityMismatch {
+                proof_commitment: [1; 32],
+                registered_commitment: [2; 32],
+            }
+        );
+        assert_eq!(error.name(), "ProofIdentityMismatch");
+
+        let data = ProofHeir::InvalidZKProof {}.abi_encode();
+        assert_eq!(
+            ProofHeirError::decode(&data),
+            Some(ProofHeirError::InvalidZkProof)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_foreign_revert_data() {
+        // Error(string) from a require() elsewhere
+        let data = alloy::sol_types::Revert {
+            reason: "nope".to_string(),
+        }
+        .abi_encode();
+        assert_eq!(ProofHeirError::decode(&data), None);
+        assert_eq!(ProofHeirError::decode(&[]), None);
+        assert_eq!(ProofHeirError::decode(&[0xde, 0xad]), None);
+    }
+}
@@ -0,0 +1,222 @@
+/// Circuit context - the compiled circuit, its SRS and verification key, loaded once per process
+///
+/// Parsing `circuits.json`, setting up the SRS and computing the verification key take seconds.
+/// The prover and verifier share one [`CircuitContext`] instead of repeating that for every proof.
+///
+/// Without an SRS file, Barretenberg downloads the SRS points the circuit needs. [`write_srs`]
+/// saves them to disk once, and `SRS_PATH` points [`CircuitContext::global`] at that file so
+/// proving works without network access.
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use k256::sha2::{Digest, Sha256};
+use noir::barretenberg::{
+    srs::{get_srs, localsrs::LocalSrs, setup_srs_from_bytecode},
+    utils::get_subgroup_size,
+    verify::get_ultra_honk_verification_key,
+};
+use serde_json::Value;
+
+use crate::error::{NotaryError, Result};
+use crate::inputs::decode_hex;
+use crate::proof_layout::ProofLayout;
+use crate::types::PUBLIC_INPUT_FIELDS;
+
+/// Compiled Noir circuit embedded at build time (from packages/circuits)
+pub const PROGRAM_JSON: &str = include_str!("../circuits.json");
+
+/// Bytecode, proof layout and verification key of a circuit whose SRS is set up
+///
+/// The SRS lives in Barretenberg's process-wide state; loading a context sets it up for the
+/// circuit's size, so proving and verifying with [`bytecode`](Self::bytecode) need no further setup.
+pub struct CircuitContext {
+    bytecode: String,
+    layout: ProofLayout,
+    vk: Vec<u8>,
+    vk_hash: [u8; 32],
+}
+
+impl CircuitContext {
+    /// Parse a compiled program, set up its SRS and compute its verification key
+    ///
+    /// The SRS is read from `srs_path` (a file written by [`write_srs`]) or, when `None`,
+    /// downloaded. Blocks for seconds: call it from a blocking thread (see
+    /// [`ProvingPool`](crate::proving::ProvingPool)) or at startup.
+    pub fn load(program_json: &str, srs_path: Option<&Path>) -> Result<Self> {
+        // A missing SRS file is a configuration error, reported before any circuit work
+        let srs_path = srs_path.map(srs_file).transpose()?;
+
+        let json: Value = serde_json::from_str(program_json).map_err(NotaryError::zk)?;
+        let bytecode = bytecode(&json)?;
+
+        // Public-input count comes from the circuit ABI; it must agree with our serialization
+        let layout = ProofLayout::from_program_json(&json)?;
+        if layout.public_input_fields() != PUBLIC_INPUT_FIELDS {
+            return Err(NotaryError::zk(format!(
+                "circuits.json declares {} public input fields but the notary serializes {}; rebuild it with scripts/build-circuit.sh",
+                layout.public_input_fields(),
+                PUBLIC_INPUT_FIELDS
+            )));
+        }
+
+        setup_srs_from_bytecode(&bytecode, srs_path, false).map_err(NotaryError::zk)?;
+        let vk = get_ultra_honk_verification_key(&bytecode, false).map_err(NotaryError::zk)?;
+        // Proof size and public input count come from constants and the ABI; the VK must agree
+        layout.check_vk(&vk)?;
+        let vk_hash = Sha256::digest(&vk).into();
+
+        Ok(Self {
+            bytecode,
+            layout,
+            vk,
+            vk_hash,
+        })
+    }
+
+    /// Process-wide context for the embedded `circuits.json`, loaded on first use
+    ///
+    /// The SRS comes from the file at `SRS_PATH` when set. When `CIRCUIT_VK_HASH` is set, the
+    /// verification key must hash to it, so a circuit build that does not match the deployed
+    /// `Verifier.sol` is refused before any proof. Blocks on first use, like [`load`](Self::load).
+    ///
+    /// Only a successful load is kept: after an error (e.g. `SRS_PATH` not written yet) the next
+    /// call tries again.
+    pub fn global() -> Result<&'static CircuitContext> {
+        static CIRCUIT: OnceLock<CircuitContext> = OnceLock::new();
+        static LOADING: Mutex<()> = Mutex::new(());
+
+        if let Some(circuit) = CIRCUIT.get() {
+            return Ok(circuit);
+        }
+
+        // One load at a time; callers waiting here reuse its result if it succeeded
+        let _loading = LOADING.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(circuit) = CIRCUIT.get() {
+            return Ok(circuit);
+        }
+        let circuit = Self::load_from_env()?;
+        Ok(CIRCUIT.get_or_init(|| circuit))
+    }
+
+    fn load_from_env() -> Result<Self> {
+        let srs_path = std::env::var("SRS_PATH")
+            .ok()
+            .filter(|path| !path.trim().is_empty());
+        let circuit = Self::load(PROGRAM_JSON, srs_path.as_deref().map(Path::new))?;
+        tracing::info!(
+            "🔑 Circuit loaded: VK {} bytes, hash 0x{}",
+            circuit.vk.len(),
+            hex::encode(circuit.vk_hash)
+        );
+
+        if let Ok(expected) = std::env::var("CIRCUIT_VK_HASH") {
+            if !expected.trim().is_empty() {
+                circuit.check_vk_hash(expected.trim())?;
+            }
+        }
+        Ok(circuit)
+    }
+
+    pub fn bytecode(&self) -> &str {
+        &self.bytecode
+    }
+
+    pub fn layout(&self) -> ProofLayout {
+        self.layout
+    }
+
+    pub fn vk(&self) -> &[u8] {
+        &self.vk
+    }
+
+    /// SHA256 of the verification key
+    pub fn vk_hash(&self) -> [u8; 32] {
+        self.vk_hash
+    }
+
+    /// Fail unless the verification key hashes to `expected` (64 hex characters, `0x` optional)
+    pub fn check_vk_hash(&self, expected: &str) -> Result<()> {
+        check_vk_hash(&self.vk_hash, expected)
+    }
+}
+
+/// Download the SRS points the circuit in `program_json` needs and save them to `path`
+///
+/// The file can then be passed to [`CircuitContext::load`] (or set as `SRS_PATH`) on machines
+/// without network access. Returns the number of points written.
+pub fn write_srs(program_json: &str, path: &Path) -> Result<u32> {
+    let json: Value = serde_json::from_str(program_json).map_err(NotaryError::zk)?;
+    let bytecode = bytecode(&json)?;
+    let path = path
+        .to_str()
+        .ok_or_else(|| NotaryError::invalid_input("SRS path must be valid UTF-8"))?;
+
+    let srs = get_srs(get_subgroup_size(&bytecode, false), None);
+    let points = srs.num_points;
+    LocalSrs(srs).save(Some(path));
+    Ok(points)
+}
+
+fn bytecode(json: &Value) -> Result<String> {
+    json["bytecode"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| NotaryError::zk("bytecode field not found in program.json"))
+}
+
+/// `path` as Barretenberg takes it, failing early (instead of panicking there) if it is missing
+fn srs_file(path: &Path) -> Result<&str> {
+    if !path.is_file() {
+        return Err(NotaryError::zk(format!(
+            "SRS file {} not found; write it with `notary srs --out {}`",
+            path.display(),
+            path.display()
+        )));
+    }
+    path.to_str()
+        .ok_or_else(|| NotaryError::invalid_input("SRS path must be valid UTF-8"))
+}
+
+fn check_vk_hash(vk_hash: &[u8; 32], expected: &str) -> Result<()> {
+    let expected: [u8; 32] = decode_hex(expected, "Expected VK hash")?;
+    if *vk_hash != expected {
+        return Err(NotaryError::zk(format!(
+            "Circuit verification key hash 0x{} does not match the expected 0x{}; \
+             circuits.json is not the build the contracts were deployed with",
+            hex::encode(vk_hash),
+            hex::encode(expected)
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vk_hash_check() {
+        let vk_hash: [u8; 32] = Sha256::digest(b"vk").into();
+        let hex_hash = hex::encode(vk_hash);
+
+        assert!(check_vk_hash(&vk_hash, &hex_hash).is_ok());
+        assert!(check_vk_hash(&vk_hash, &format!("0x{}", hex_hash)).is_ok());
+
+        let error = check_vk_hash(&vk_hash, &"00".repeat(32)).unwrap_err();
+        assert_eq!(error.code(), "ZK_PROOF_FAILED");
+        assert!(error.to_string().contains(&hex_hash));
+
+        assert!(check_vk_hash(&vk_hash, "0x1234").is_err());
+    }
+
+    #[test]
+    fn test_missing_srs_file_fails_before_setup() {
+        let path = std::env::temp_dir().join("proofheir-missing-srs.local");
+        // Not even parsed: the SRS path is checked first
+        let error = CircuitContext::load("not json", Some(&path))
+            .err()
+            .expect("missing SRS file must fail");
+        assert_eq!(error.code(), "ZK_PROOF_FAILED");
+        assert!(error.to_string().contains("notary srs --out"));
+    }
+}
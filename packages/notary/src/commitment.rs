@@ -0,0 +1,79 @@
+/// Identity commitment - the value a testator registers with `registerIdentity`
+///
+/// `id_commitment = SHA256(nuip || 0x00 padding to 15 bytes || salt)`, exactly as the circuit
+/// pads `nuip: str<15>`. The proof only verifies on-chain if the registered value was computed
+/// this way.
+use k256::sha2::{Digest, Sha256};
+
+use crate::inputs::{Nuip, Salt};
+
+/// `id_commitment` for `nuip` and `salt`
+pub fn id_commitment(nuip: &Nuip, salt: Salt) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(nuip.padded());
+    hasher.update(salt.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Whether `commitment` is the `id_commitment` of `nuip` and `salt`
+pub fn verify_id_commitment(nuip: &Nuip, salt: Salt, commitment: &[u8; 32]) -> bool {
+    id_commitment(nuip, salt) == *commitment
+}
+
+/// Published `id_commitment` test vector (hex without `0x`)
+#[derive(Debug, Clone, Copy)]
+pub struct TestVector {
+    pub nuip: &'static str,
+    pub salt: &'static str,
+    pub id_commitment: &'static str,
+}
+
+/// Reference values for other implementations (frontend, scripts) to check against
+pub const TEST_VECTORS: [TestVector; 4] = [
+    TestVector {
+        nuip: "454545454",
+        salt: "1111111111111111111111111111111111111111111111111111111111111111",
+        id_commitment: "31ea7f1c681941572e945df8eda8001c43d9e49e85103fa1bace7e5de5a3b19a",
+    },
+    TestVector {
+        nuip: "123456789",
+        salt: "0000000000000000000000000000000000000000000000000000000000000000",
+        id_commitment: "50452233eb8f434b9f9abdb98f4f57ea9db20a79cc44c2fcc160da0deb26d3de",
+    },
+    TestVector {
+        nuip: "1",
+        salt: "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        id_commitment: "5b367ddffb4890d0eb2e6ffff680d0c63f63c05230fa1254ba61639789d61fdc",
+    },
+    // Exactly 15 bytes: no padding
+    TestVector {
+        nuip: "123456789012345",
+        salt: "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        id_commitment: "1f0b237e8acc381473c33f967cf636101e5c0319da94b1db99b3110435138f69",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        for vector in TEST_VECTORS {
+            let nuip = Nuip::new(vector.nuip).unwrap();
+            let salt: Salt = vector.salt.parse().unwrap();
+            let expected: [u8; 32] = hex::decode(vector.id_commitment)
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+            assert_eq!(id_commitment(&nuip, salt), expected, "{:?}", vector);
+            assert!(verify_id_commitment(&nuip, salt, &expected));
+            assert!(!verify_id_commitment(
+                &nuip,
+                Salt::from([0xaa; 32]),
+                &expected
+            ));
+        }
+    }
+}
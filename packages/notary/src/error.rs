@@ -0,0 +1,145 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::chain::ProofHeirError;
+use crate::types::PublicInputMismatch;
+
+/// Errors produced by the notary crate, one variant per stage of the proof flow
+///
+/// Every variant carries a stable machine-readable code (see [`NotaryError::code`])
+/// so callers such as the API can map failures without inspecting messages.
+#[derive(Debug, Error)]
+pub enum NotaryError {
+    /// Caller supplied malformed parameters (NUIP, URI, addresses, salt)
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Civil registry could not be reached or answered with an unexpected status
+    #[error("civil registry unreachable: {0}")]
+    RegistryUnreachable(String),
+
+    /// Registry reports the subject as alive, so a proof of death cannot exist
+    #[error("subject is alive according to the civil registry")]
+    SubjectAlive,
+
+    /// MPC-TLS session between prover and verifier failed
+    #[error("MPC-TLS failure: {0}")]
+    MpcTls(String),
+
+    /// The TLS-authenticated server is not an accepted registry domain
+    #[error("untrusted registry: {0}")]
+    UntrustedRegistry(String),
+
+    /// A transcript commitment does not match the value it should bind
+    #[error("commitment mismatch: {0}")]
+    CommitmentMismatch(String),
+
+    /// Public inputs claimed in the bundle differ from the ones the proof commits to
+    #[error("public inputs do not match the proof: {}", join_mismatches(.0))]
+    PublicInputsMismatch(Vec<PublicInputMismatch>),
+
+    /// Noir witness generation or UltraHonk proving failed
+    #[error("ZK proving failed: {0}")]
+    ZkProof(String),
+
+    /// The UltraHonk proof did not verify against the pinned verification key
+    #[error("invalid ZK proof: {0}")]
+    InvalidProof(String),
+
+    /// On-chain state already rules the proof out (checked before MPC-TLS); carries the error
+    /// `proveDeathAndRegisterHeir` would revert with
+    #[error("on-chain pre-flight check failed: {0}")]
+    Preflight(ProofHeirError),
+
+    /// Sending or confirming the on-chain transaction failed
+    #[error("on-chain submission failed: {0}")]
+    OnChain(String),
+
+    /// ProofHeir rejected the transaction with one of its custom errors
+    #[error("contract reverted: {0}")]
+    ContractReverted(ProofHeirError),
+
+    /// Unexpected failure not attributable to a specific stage (task panics, encoding)
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl NotaryError {
+    /// Stable machine-readable code for this error
+    pub fn code(&self) -> &'static str {
+        match self {
+            NotaryError::InvalidInput(_) => "INVALID_INPUT",
+            NotaryError::RegistryUnreachable(_) => "REGISTRY_UNREACHABLE",
+            NotaryError::SubjectAlive => "SUBJECT_ALIVE",
+            NotaryError::MpcTls(_) => "MPC_TLS_FAILED",
+            NotaryError::UntrustedRegistry(_) => "UNTRUSTED_REGISTRY",
+            NotaryError::CommitmentMismatch(_) => "COMMITMENT_MISMATCH",
+            NotaryError::PublicInputsMismatch(_) => "PUBLIC_INPUTS_MISMATCH",
+            NotaryError::ZkProof(_) => "ZK_PROOF_FAILED",
+            NotaryError::InvalidProof(_) => "INVALID_PROOF",
+            NotaryError::Preflight(_) => "PREFLIGHT_FAILED",
+            NotaryError::OnChain(_) => "ONCHAIN_FAILED",
+            NotaryError::ContractReverted(_) => "CONTRACT_REVERTED",
+            NotaryError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Rebuild an error reported by a remote notary from its code and message
+    ///
+    /// Variants carrying structured detail (`PUBLIC_INPUTS_MISMATCH`, `PREFLIGHT_FAILED`,
+    /// `CONTRACT_REVERTED`) are rebuilt from `SessionOutcome` instead.
+    pub(crate) fn from_remote(code: &str, message: String) -> Self {
+        match code {
+            "INVALID_INPUT" => NotaryError::InvalidInput(message),
+            "REGISTRY_UNREACHABLE" => NotaryError::RegistryUnreachable(message),
+            "SUBJECT_ALIVE" => NotaryError::SubjectAlive,
+            "MPC_TLS_FAILED" => NotaryError::MpcTls(message),
+            "UNTRUSTED_REGISTRY" => NotaryError::UntrustedRegistry(message),
+            "COMMITMENT_MISMATCH" => NotaryError::CommitmentMismatch(message),
+            "ZK_PROOF_FAILED" => NotaryError::ZkProof(message),
+            "INVALID_PROOF" => NotaryError::InvalidProof(message),
+            "ONCHAIN_FAILED" => NotaryError::OnChain(message),
+            _ => NotaryError::Internal(format!("notary reported {}: {}", code, message)),
+        }
+    }
+
+    pub(crate) fn invalid_input(e: impl Display) -> Self {
+        NotaryError::InvalidInput(e.to_string())
+    }
+
+    pub(crate) fn registry(e: impl Display) -> Self {
+        NotaryError::RegistryUnreachable(e.to_string())
+    }
+
+    pub(crate) fn mpc_tls(e: impl Display) -> Self {
+        NotaryError::MpcTls(e.to_string())
+    }
+
+    pub(crate) fn commitment(e: impl Display) -> Self {
+        NotaryError::CommitmentMismatch(e.to_string())
+    }
+
+    pub(crate) fn zk(e: impl Display) -> Self {
+        NotaryError::ZkProof(e.to_string())
+    }
+
+    pub(crate) fn on_chain(e: impl Display) -> Self {
+        NotaryError::OnChain(e.to_string())
+    }
+
+    pub(crate) fn internal(e: impl Display) -> Self {
+        NotaryError::Internal(e.to_string())
+    }
+}
+
+fn join_mismatches(mismatches: &[PublicInputMismatch]) -> String {
+    mismatches
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Result alias used throughout the notary crate
+pub type Result<T, E = NotaryError> = std::result::Result<T, E>;
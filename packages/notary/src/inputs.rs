@@ -0,0 +1,378 @@
+/// Validated inputs - parsed once at the boundary (CLI, API, library callers)
+///
+/// The circuit takes fixed-size inputs (`nuip: str<15>`, `server_domain: str<40>`), so anything
+/// longer can never produce a proof that matches. These types reject such values with a precise
+/// message instead of letting them be truncated later.
+use std::fmt;
+use std::str::FromStr;
+
+use hyper::Uri;
+use k256::sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{NotaryError, Result};
+
+/// Length the circuit pads the NUIP to (`nuip: str<15>`)
+pub const NUIP_LEN: usize = 15;
+
+/// Length the circuit pads the server domain to (`server_domain: str<40>`)
+pub const SERVER_DOMAIN_LEN: usize = 40;
+
+/// Strip an optional `0x`/`0X` prefix and decode exactly `N` bytes of hex
+pub(crate) fn decode_hex<const N: usize>(value: &str, what: &str) -> Result<[u8; N]> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+
+    if digits.len() != N * 2 {
+        return Err(NotaryError::invalid_input(format!(
+            "{} must be {} bytes ({} hex characters), got {} characters",
+            what,
+            N,
+            N * 2,
+            digits.len()
+        )));
+    }
+
+    let mut bytes = [0u8; N];
+    hex::decode_to_slice(digits, &mut bytes)
+        .map_err(|e| NotaryError::invalid_input(format!("{} is not valid hex: {}", what, e)))?;
+    Ok(bytes)
+}
+
+/// National ID number (NUIP): 1 to [`NUIP_LEN`] ASCII letters or digits
+///
+/// `Debug` is redacted - the NUIP is never logged.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Nuip(String);
+
+impl Nuip {
+    pub fn new(nuip: impl Into<String>) -> Result<Self> {
+        let nuip = nuip.into();
+        if nuip.is_empty() {
+            return Err(NotaryError::invalid_input("NUIP cannot be empty"));
+        }
+        if nuip.len() > NUIP_LEN {
+            return Err(NotaryError::invalid_input(format!(
+                "NUIP is {} bytes, the circuit accepts at most {}",
+                nuip.len(),
+                NUIP_LEN
+            )));
+        }
+        if let Some(c) = nuip.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(NotaryError::invalid_input(format!(
+                "NUIP may only contain ASCII letters and digits, found {:?}",
+                c
+            )));
+        }
+        Ok(Self(nuip))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// NUIP padded with zeros to [`NUIP_LEN`] bytes - MUST match circuit padding
+    pub fn padded(&self) -> [u8; NUIP_LEN] {
+        let mut padded = [0u8; NUIP_LEN];
+        padded[..self.0.len()].copy_from_slice(self.0.as_bytes());
+        padded
+    }
+}
+
+impl fmt::Debug for Nuip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Nuip(..)")
+    }
+}
+
+impl FromStr for Nuip {
+    type Err = NotaryError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for Nuip {
+    type Error = NotaryError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl From<Nuip> for String {
+    fn from(nuip: Nuip) -> Self {
+        nuip.0
+    }
+}
+
+/// 32-byte salt for the identity commitment, parsed from 64 hex characters (`0x` optional)
+///
+/// `Debug` is redacted - the salt is never logged.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Salt([u8; 32]);
+
+impl Salt {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Salt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Salt(..)")
+    }
+}
+
+impl From<[u8; 32]> for Salt {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl FromStr for Salt {
+    type Err = NotaryError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        decode_hex(s, "Salt").map(Self)
+    }
+}
+
+impl TryFrom<String> for Salt {
+    type Error = NotaryError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<Salt> for String {
+    fn from(salt: Salt) -> Self {
+        format!("0x{}", hex::encode(salt.0))
+    }
+}
+
+/// 20-byte Ethereum address, parsed from 40 hex characters (`0x` optional)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EthAddress([u8; 20]);
+
+impl EthAddress {
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl fmt::Display for EthAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for EthAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EthAddress({})", self)
+    }
+}
+
+impl From<[u8; 20]> for EthAddress {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<EthAddress> for [u8; 20] {
+    fn from(address: EthAddress) -> Self {
+        address.0
+    }
+}
+
+impl FromStr for EthAddress {
+    type Err = NotaryError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        decode_hex(s, "Ethereum address").map(Self)
+    }
+}
+
+impl TryFrom<String> for EthAddress {
+    type Error = NotaryError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<EthAddress> for String {
+    fn from(address: EthAddress) -> Self {
+        address.to_string()
+    }
+}
+
+/// Civil registry domain: 1 to [`SERVER_DOMAIN_LEN`] printable ASCII characters without spaces
+///
+/// Spaces are the circuit's padding, so a domain containing them would be ambiguous.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RegistryDomain(String);
+
+impl RegistryDomain {
+    pub fn new(domain: impl Into<String>) -> Result<Self> {
+        let domain = domain.into();
+        if domain.is_empty() {
+            return Err(NotaryError::invalid_input(
+                "Registry domain cannot be empty",
+            ));
+        }
+        if domain.len() > SERVER_DOMAIN_LEN {
+            return Err(NotaryError::invalid_input(format!(
+                "Registry domain '{}' is {} bytes, the circuit accepts at most {}",
+                domain,
+                domain.len(),
+                SERVER_DOMAIN_LEN
+            )));
+        }
+        if let Some(c) = domain.chars().find(|c| !c.is_ascii_graphic()) {
+            return Err(NotaryError::invalid_input(format!(
+                "Registry domain '{}' contains {:?}; only printable ASCII without spaces is allowed",
+                domain, c
+            )));
+        }
+        Ok(Self(domain))
+    }
+
+    /// Host of a registry endpoint URL
+    pub fn from_url(url: &str) -> Result<Self> {
+        let uri = url.parse::<Uri>().map_err(NotaryError::invalid_input)?;
+        let host = uri
+            .host()
+            .ok_or_else(|| NotaryError::invalid_input("URI must have authority"))?;
+        Self::new(host)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Domain padded with spaces to [`SERVER_DOMAIN_LEN`] bytes - MUST match circuit padding
+    pub fn padded(&self) -> [u8; SERVER_DOMAIN_LEN] {
+        let mut padded = [b' '; SERVER_DOMAIN_LEN];
+        padded[..self.0.len()].copy_from_slice(self.0.as_bytes());
+        padded
+    }
+
+    /// SHA256 of the padded domain
+    /// This is the `server_hash` public input and the contract's `trustedServerHash`
+    pub fn server_hash(&self) -> [u8; 32] {
+        Sha256::digest(self.padded()).into()
+    }
+}
+
+impl fmt::Display for RegistryDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for RegistryDomain {
+    type Err = NotaryError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for RegistryDomain {
+    type Error = NotaryError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl From<RegistryDomain> for String {
+    fn from(domain: RegistryDomain) -> Self {
+        domain.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(error: NotaryError) -> String {
+        error.to_string()
+    }
+
+    #[test]
+    fn test_nuip_limits() {
+        assert_eq!(&Nuip::new("12").unwrap().padded()[..3], b"12\0");
+        assert!(Nuip::new("123456789012345").is_ok());
+
+        assert!(message(Nuip::new("").unwrap_err()).contains("cannot be empty"));
+        assert!(message(Nuip::new("1234567890123456").unwrap_err()).contains("16 bytes"));
+        assert!(message(Nuip::new("1234 5").unwrap_err()).contains("' '"));
+        assert_eq!(format!("{:?}", Nuip::new("454545454").unwrap()), "Nuip(..)");
+    }
+
+    #[test]
+    fn test_hex_parsing() {
+        let salt: Salt = "0x1111111111111111111111111111111111111111111111111111111111111111"
+            .parse()
+            .unwrap();
+        assert_eq!(salt, Salt::from([0x11; 32]));
+        assert_eq!(format!("{:?}", salt), "Salt(..)");
+        assert!(message("1111".parse::<Salt>().unwrap_err()).contains("got 4 characters"));
+
+        let address: EthAddress = "ABABABABABABABABABABABABABABABABABABABAB".parse().unwrap();
+        assert_eq!(address, EthAddress::from([0xab; 20]));
+        assert_eq!(address.to_string(), format!("0x{}", "ab".repeat(20)));
+        assert!(
+            message("zz".repeat(20).parse::<EthAddress>().unwrap_err()).contains("not valid hex")
+        );
+    }
+
+    #[test]
+    fn test_registry_domain_limits() {
+        let domain =
+            RegistryDomain::from_url("https://localhost:8443/VigenciaCedula/consulta").unwrap();
+        assert_eq!(domain.as_str(), "localhost");
+        assert_eq!(&domain.padded()[..10], b"localhost ");
+
+        let long = format!("{}.example.com", "a".repeat(30));
+        assert!(message(RegistryDomain::new(long).unwrap_err()).contains("at most 40"));
+        assert!(RegistryDomain::new("bad domain").is_err());
+        assert!(RegistryDomain::new("").is_err());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let json = serde_json::json!({
+            "nuip": "454545454",
+            "salt": "11".repeat(32),
+            "address": "cd".repeat(20),
+        });
+
+        #[derive(Deserialize, Serialize)]
+        struct Inputs {
+            nuip: Nuip,
+            salt: Salt,
+            address: EthAddress,
+        }
+
+        let inputs: Inputs = serde_json::from_value(json).unwrap();
+        assert_eq!(inputs.nuip.as_str(), "454545454");
+        assert_eq!(inputs.address, EthAddress::from([0xcd; 20]));
+
+        let serialized = serde_json::to_value(&inputs).unwrap();
+        assert_eq!(serialized["address"], format!("0x{}", "cd".repeat(20)));
+
+        let invalid = serde_json::json!({ "nuip": "", "salt": "11", "address": "cd" });
+        assert!(serde_json::from_value::<Inputs>(invalid).is_err());
+    }
+}
@@ -1,11 +1,34 @@
+pub mod chain;
+pub mod circuit;
+pub mod commitment;
+pub mod error;
+pub mod inputs;
+pub mod preflight;
+pub mod progress;
+pub mod proving;
 pub mod prover;
 pub mod types;
 pub mod proof_gen;
+pub mod proof_layout;
+pub mod registry;
+pub mod remote;
+pub mod submitter;
+pub mod tls;
 pub mod verifier;  // Already public
 
+pub use chain::{ChainClient, ProofHeirError, TxOutcome, TxReceipt};
+pub use circuit::CircuitContext;
+pub use error::NotaryError;
+pub use inputs::{EthAddress, Nuip, RegistryDomain, Salt};
 pub use prover::prover;
-pub use types::{SolidityProofBundle, ZKProofBundle, PublicInputs, serialize_public_inputs_for_solidity};
-pub use proof_gen::{generate_death_proof, ProofGenerationResult};
+pub use types::{SolidityProofBundle, ZKProofBundle, PublicInputs, PublicInputMismatch, serialize_public_inputs_for_solidity};
+pub use proof_gen::{generate_death_proof, ProofGenConfig, ProofGenerationResult};
+pub use progress::{Progress, ProgressEvent, ProofStage};
+pub use proving::ProvingPool;
+pub use registry::{ColombiaRegistry, RegistryAdapter};
+pub use submitter::{ChainSubmitter, ClaimSubmitter, Submission, SubmissionMode};
+pub use tls::TlsTrust;
+pub use verifier::VerifiedClaim;
 
 /// High-level API for generating a ZK proof bundle ready for smart contract verification
 /// 
@@ -16,19 +39,19 @@ pub use proof_gen::{generate_death_proof, ProofGenerationResult};
 /// 4. Serializes proof and public inputs for Solidity
 /// 
 /// # Arguments
-/// * `recipient` - Ethereum address of the heir (20 bytes)
+/// * `recipient` - Ethereum address of the heir
 /// * `nuip` - National ID number (e.g., "123456789")
 /// * `salt` - 32-byte salt for identity commitment
 /// * `testator_address` - Ethereum address of the testator (delegated account)
 /// 
 /// # Returns
-/// `SolidityProofBundle` containing hex-encoded proof and 116 public input fields
+/// `SolidityProofBundle` containing hex-encoded proof and 148 public input fields
 pub async fn generate_proof_for_contract(
-    recipient: [u8; 20],
-    nuip: String,
-    salt: [u8; 32],
-    testator_address: [u8; 20],
-) -> anyhow::Result<SolidityProofBundle> {
+    recipient: EthAddress,
+    nuip: Nuip,
+    salt: Salt,
+    testator_address: EthAddress,
+) -> Result<SolidityProofBundle, NotaryError> {
     let result = proof_gen::generate_death_proof(recipient, nuip, salt, testator_address).await?;
     
     Ok(SolidityProofBundle {
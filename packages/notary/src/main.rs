@@ -1,26 +1,108 @@
-use notary::prover::prover;
-use notary::verifier::verifier;
-use clap::Parser;
+use alloy::primitives::B256;
+use notary::circuit::{write_srs, CircuitContext, PROGRAM_JSON};
+use notary::commitment::{id_commitment, verify_id_commitment};
+use notary::prover::{prover, ProverParams};
+use notary::registry::{resolve_registry_addr, ColombiaRegistry, RegistryAdapter};
+use notary::remote::prove_with_remote_notary;
+use notary::tls::TlsTrust;
+use notary::verifier::{verifier, VerifierOptions};
+use notary::{EthAddress, NotaryError, Nuip, Progress, ProvingPool, Salt, SubmissionMode};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// TLSNotary Prover for generating ZK proofs of death certificates
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Recipient Ethereum address (40 hex characters without 0x prefix)
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Recipient Ethereum address (40 hex characters, 0x optional)
     #[arg(short, long, default_value = "abababababababababababababababababababab")]
-    recipient: String,
+    recipient: EthAddress,
 
-    /// National ID number (NUIP)
+    /// National ID number (NUIP, at most 15 characters)
     #[arg(short, long, default_value = "454545454")]
-    nuip: String,
+    nuip: Nuip,
 
     /// Salt for ID commitment (64 hex characters)
     #[arg(short, long, default_value = "1111111111111111111111111111111111111111111111111111111111111111")]
-    salt: String,
+    salt: Salt,
 
-    /// Testator (delegated account) Ethereum address (40 hex characters without 0x prefix)
+    /// Testator (delegated account) Ethereum address (40 hex characters, 0x optional)
     #[arg(short, long, default_value = "0000000000000000000000000000000000000000")]
-    testator: String,
+    testator: EthAddress,
+
+    /// Remote notary address (host:port, see `notary_server`); the verifier runs in-process when omitted
+    #[arg(long)]
+    notary: Option<String>,
+
+    /// What the verifier does with the proof: verify_only, dry_run, submit or calldata
+    /// (default: `SUBMISSION_MODE`, else verify_only with --notary and submit in-process)
+    #[arg(long)]
+    submission: Option<SubmissionMode>,
+
+    /// Simulate proveDeathAndRegisterHeir with eth_call instead of sending it (--submission dry_run)
+    #[arg(long, conflicts_with = "submission")]
+    dry_run: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compute the identity commitment to register with `registerIdentity`
+    Commitment {
+        /// National ID number (NUIP, at most 15 characters)
+        #[arg(short, long)]
+        nuip: Nuip,
+
+        /// Salt for ID commitment (64 hex characters)
+        #[arg(short, long)]
+        salt: Salt,
+
+        /// Registered commitment to check instead of printing a new one (64 hex characters)
+        #[arg(long)]
+        verify: Option<B256>,
+    },
+
+    /// Download the SRS points the embedded circuit needs and save them for offline proving
+    Srs {
+        /// File to write; pass it as `SRS_PATH` to the API, notary_server or this prover
+        #[arg(short, long, default_value = "srs.local")]
+        out: PathBuf,
+    },
+}
+
+/// `notary commitment`: prints (or checks) `SHA256(nuip padded to 15 bytes || salt)`
+fn commitment(
+    nuip: &Nuip,
+    salt: Salt,
+    verify: Option<B256>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match verify {
+        None => println!("0x{}", hex::encode(id_commitment(nuip, salt))),
+        Some(expected) => {
+            if !verify_id_commitment(nuip, salt, &expected.0) {
+                return Err("Commitment does not match NUIP and salt".into());
+            }
+            println!("✅ Commitment matches");
+        }
+    }
+    Ok(())
+}
+
+/// `notary srs`: writes the SRS to `out`, then loads the circuit from it to check the file
+async fn srs(out: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    println!("⬇️ Downloading SRS for the embedded circuit...");
+    let circuit = tokio::task::spawn_blocking(move || {
+        let points = write_srs(PROGRAM_JSON, &out)?;
+        println!("💾 Wrote {} SRS points to {}", points, out.display());
+        CircuitContext::load(PROGRAM_JSON, Some(&out))
+    })
+    .await??;
+
+    println!("✅ Circuit loads offline from the file (VK hash 0x{})", hex::encode(circuit.vk_hash()));
+    Ok(())
 }
 
 #[tokio::main]
@@ -29,86 +111,86 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let args = Args::parse();
 
-    // Parse recipient address
-    let recipient_hex = args.recipient.trim_start_matches("0x");
-    if recipient_hex.len() != 40 {
-        return Err("Recipient address must be 40 hex characters (20 bytes)".into());
-    }
-    let recipient_bytes = hex::decode(recipient_hex)
-        .map_err(|_| "Invalid recipient address hex")?;
-    let mut recipient = [0u8; 20];
-    recipient.copy_from_slice(&recipient_bytes);
-
-    // Parse salt
-    let salt_hex = args.salt.trim_start_matches("0x");
-    if salt_hex.len() != 64 {
-        return Err("Salt must be 64 hex characters (32 bytes)".into());
-    }
-    let salt_bytes = hex::decode(salt_hex)
-        .map_err(|_| "Invalid salt hex")?;
-    let mut salt = [0u8; 32];
-    salt.copy_from_slice(&salt_bytes);
-
-    // Parse testator address
-    let testator_hex = args.testator.trim_start_matches("0x");
-    if testator_hex.len() != 40 {
-        return Err("Testator address must be 40 hex characters (20 bytes)".into());
+    match &args.command {
+        Some(Command::Commitment { nuip, salt, verify }) => return commitment(nuip, *salt, *verify),
+        Some(Command::Srs { out }) => return srs(out.clone()).await,
+        None => {}
     }
-    let testator_bytes = hex::decode(testator_hex)
-        .map_err(|_| "Invalid testator address hex")?;
-    let mut testator_address = [0u8; 20];
-    testator_address.copy_from_slice(&testator_bytes);
 
-    // Validate NUIP
+    // Addresses, salt and NUIP are validated by clap through their FromStr impls
+    let recipient = args.recipient;
+    let salt = args.salt;
+    let testator_address = args.testator;
     let nuip = args.nuip.clone();
-    if nuip.is_empty() {
-        return Err("NUIP cannot be empty".into());
-    }
 
-    // Configuration for the target server (Civil Registry Mock on Railway)
-    let host = "web-production-05160.up.railway.app";
-    let port = 443;
-    let server_addr = tokio::net::lookup_host((host, port))
-        .await?
-        .next()
-        .ok_or("Failed to lookup host")?;
-    
-    // URI to access
-    let uri = "https://web-production-05160.up.railway.app/VigenciaCedula/consulta";
+    // Target server (Civil Registry Mock on Railway unless overridden by env)
+    let registry = ColombiaRegistry::from_env();
+    let server_addr = resolve_registry_addr(&registry).await?;
+    let uri = registry.url();
 
     println!("Starting prover test against {} ({})", uri, server_addr);
 
+    // CA bundles, native roots and SPKI pins (TLS_CA_BUNDLES, TLS_NATIVE_ROOTS, TLS_SPKI_PINS)
+    let tls = TlsTrust::from_env();
+
     // [New] Pre-verification check to fail fast if subject is Alive
     println!("🔍 Running pre-verification check...");
-    let client = reqwest::Client::new();
-    let nuip_number: u64 = nuip.parse()
-        .map_err(|_| "Invalid NUIP format for pre-verification")?;
-    let res = client.post(uri)
-        .json(&serde_json::json!({
-            "nuip": nuip_number,
-            "ip": "143.137.96.53"
-        }))
-        .send()
-        .await?;
-
-    let body: serde_json::Value = res.json().await?;
-    println!("Server response: {:?}", body);
-
-    if let Some(vigencia) = body.get("vigencia").and_then(|v| v.as_str()) {
-        if vigencia == "Vigente (Vivo)" {
-            tracing::error!("❌ Pre-verification FAILED: Subject is 'Vigente (Vivo)'.\nCannot generate 'Proof of Death'. Aborting.");
+    match registry.pre_check(&nuip, &tls).await {
+        Ok(()) => {}
+        Err(NotaryError::SubjectAlive) => {
+            tracing::error!("❌ Pre-verification FAILED: Subject is alive.\nCannot generate 'Proof of Death'. Aborting.");
             return Ok(());
         }
+        Err(e) => return Err(e.into()),
     }
     println!("✅ Pre-verification passed. Starting MPC-TLS...");
 
+    let submission = match args.submission {
+        Some(submission) => submission,
+        None if args.dry_run => SubmissionMode::DryRun,
+        None => SubmissionMode::from_env(args.notary.is_some()),
+    };
+
+    let progress = Progress::default();
+    let params = ProverParams {
+        server_addr,
+        registry: &registry,
+        tls: &tls,
+        recipient,
+        nuip: &nuip,
+        salt,
+        progress: &progress,
+        proving: ProvingPool::global(),
+    };
+
+    if let Some(notary_addr) = &args.notary {
+        println!("🌐 Using remote notary at {}", notary_addr);
+        let (proof_bundle, _) =
+            prove_with_remote_notary(notary_addr, &params, testator_address, submission).await?;
+
+        println!("\n📦 Proof Bundle Generated:");
+        println!("   Proof size: {} bytes", proof_bundle.proof.len());
+        println!("   VK size: {} bytes", proof_bundle.vk.len());
+        println!("---");
+        println!("Notary {} verified the session ✅", notary_addr);
+        return Ok(());
+    }
+
+    // Verifier expects the same registry endpoint the prover queries
+    let verifier_options = VerifierOptions {
+        registry: Arc::new(registry.clone()),
+        tls: tls.clone(),
+        submission,
+        ..VerifierOptions::from_env()
+    };
+
     // Connect prover and verifier.
     let (prover_socket, verifier_socket) = tokio::io::duplex(1 << 23);
     let (prover_extra_socket, verifier_extra_socket) = tokio::io::duplex(1 << 23);
 
-    let (proof_bundle, transcript) = tokio::try_join!(
-        prover(prover_socket, prover_extra_socket, &server_addr, &uri, recipient, &nuip, salt),
-        verifier(verifier_socket, verifier_extra_socket, testator_address)
+    let (proof_bundle, session) = tokio::try_join!(
+        prover(prover_socket, prover_extra_socket, &params),
+        verifier(verifier_socket, verifier_extra_socket, testator_address.into(), &verifier_options)
     )?;
 
     // Log proof bundle info
@@ -117,16 +199,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   VK size: {} bytes", proof_bundle.vk.len());
 
     println!("---");
-    println!("Successfully verified endpoint: {}", &uri);
+    println!("Successfully verified endpoint: {}", uri);
     println!("MPC-TLS + ZK Proof generation complete ✅\n");
 
     println!(
         "Verified sent data:\n{}",
-        bytes_to_redacted_string(transcript.sent_unsafe())
+        bytes_to_redacted_string(session.transcript.sent_unsafe())
     );
     println!(
         "Verified received data:\n{}",
-        bytes_to_redacted_string(transcript.received_unsafe())
+        bytes_to_redacted_string(session.transcript.received_unsafe())
     );
 
     println!("---");
@@ -0,0 +1,143 @@
+/// On-chain pre-flight checks - catch what ProofHeir would reject before minutes of MPC-TLS and proving
+use alloy::primitives::Address;
+
+use crate::chain::{ChainClient, ProofHeirError};
+use crate::commitment::id_commitment;
+use crate::error::{NotaryError, Result};
+use crate::inputs::{EthAddress, Nuip, Salt};
+use crate::registry::RegistryAdapter;
+
+/// Compare the testator's registered identity commitment and the contract's trusted server hash
+/// with the values the proof will carry
+///
+/// Fails with [`NotaryError::Preflight`] holding the error `proveDeathAndRegisterHeir` would revert with.
+pub async fn preflight(
+    chain: &dyn ChainClient,
+    registry: &dyn RegistryAdapter,
+    testator_address: EthAddress,
+    nuip: &Nuip,
+    salt: Salt,
+) -> Result<()> {
+    let account = Address::from(*testator_address.as_bytes());
+
+    let registered = chain.get_identity_commitment(account).await?;
+    if registered.is_zero() {
+        return Err(NotaryError::Preflight(
+            ProofHeirError::IdentityNotRegistered,
+        ));
+    }
+
+    let local = id_commitment(nuip, salt);
+    if registered.0 != local {
+        return Err(NotaryError::Preflight(
+            ProofHeirError::ProofIdentityMismatch {
+                proof_commitment: local,
+                registered_commitment: registered.0,
+            },
+        ));
+    }
+
+    let trusted = chain.trusted_server_hash(account).await?;
+    let local = registry.domain()?.server_hash();
+    if trusted.0 != local {
+        return Err(NotaryError::Preflight(ProofHeirError::InvalidDataSource {
+            proof_server_hash: local,
+            trusted_hash: trusted.0,
+        }));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::MockChain;
+    use crate::inputs::RegistryDomain;
+    use crate::registry::ColombiaRegistry;
+
+    const TESTATOR: [u8; 20] = [0xcd; 20];
+    const NUIP: &str = "454545454";
+    const SALT: [u8; 32] = [0x11; 32];
+    const URL: &str = "https://registry.example.com/VigenciaCedula/consulta";
+
+    fn nuip(nuip: &str) -> Nuip {
+        Nuip::new(nuip).unwrap()
+    }
+
+    fn server_hash(domain: &str) -> [u8; 32] {
+        RegistryDomain::new(domain).unwrap().server_hash()
+    }
+
+    fn chain() -> MockChain {
+        let chain = MockChain::default();
+        chain.register_identity(
+            TESTATOR.into(),
+            id_commitment(&nuip(NUIP), SALT.into()).into(),
+        );
+        chain.set_trusted_server_hash(server_hash("registry.example.com").into());
+        chain
+    }
+
+    async fn run(chain: &MockChain, id: &str) -> Result<()> {
+        preflight(
+            chain,
+            &ColombiaRegistry::new(URL),
+            TESTATOR.into(),
+            &nuip(id),
+            SALT.into(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_preflight_passes() {
+        assert!(run(&chain(), NUIP).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_rejects_unregistered_identity() {
+        let result = run(&MockChain::default(), NUIP).await;
+        assert!(matches!(
+            result,
+            Err(NotaryError::Preflight(
+                ProofHeirError::IdentityNotRegistered
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_preflight_rejects_other_identity() {
+        let result = run(&chain(), "123456789").await;
+        match result {
+            Err(NotaryError::Preflight(ProofHeirError::ProofIdentityMismatch {
+                proof_commitment,
+                registered_commitment,
+            })) => {
+                assert_eq!(
+                    proof_commitment,
+                    id_commitment(&nuip("123456789"), SALT.into())
+                );
+                assert_eq!(
+                    registered_commitment,
+                    id_commitment(&nuip(NUIP), SALT.into())
+                );
+            }
+            other => panic!("expected ProofIdentityMismatch, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_rejects_other_registry() {
+        let chain = chain();
+        chain.set_trusted_server_hash(server_hash("registraduria.gov.co").into());
+
+        let result = run(&chain, NUIP).await;
+        assert!(matches!(
+            result,
+            Err(NotaryError::Preflight(
+                ProofHeirError::InvalidDataSource { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+/// Stage progress of a proof run, reported to an optional callback
+///
+/// A stage starts when the run reaches it and completes when the next one starts (or the run
+/// finishes), so every completed stage carries its duration. The stage a failed run stopped at
+/// never completes.
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+/// Stages of [`generate_death_proof`](crate::proof_gen::generate_death_proof), in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofStage {
+    /// Asking the registry whether the subject is deceased
+    PreVerification,
+    /// On-chain identity and server hash checks
+    Preflight,
+    /// MPC setup with the notary
+    MpcTlsSetup,
+    /// Connecting to the registry and querying it over MPC-TLS
+    HttpRequest,
+    /// Committing to the NUIP and status in the transcript and opening the commitments
+    Commitment,
+    /// Waiting for a free slot in the [`ProvingPool`](crate::proving::ProvingPool); only
+    /// reported when every slot is busy
+    ProverQueue,
+    /// Loading the structured reference string for the circuit
+    SrsSetup,
+    /// Generating the UltraHonk proof
+    Proving,
+    /// Waiting for the notary to verify the session and proof
+    ///
+    /// With a remote notary this includes its on-chain transaction.
+    Verification,
+    /// Simulating or sending `proveDeathAndRegisterHeir`
+    Transaction,
+}
+
+impl fmt::Display for ProofStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProofStage::PreVerification => "pre_verification",
+            ProofStage::Preflight => "preflight",
+            ProofStage::MpcTlsSetup => "mpc_tls_setup",
+            ProofStage::HttpRequest => "http_request",
+            ProofStage::Commitment => "commitment",
+            ProofStage::ProverQueue => "prover_queue",
+            ProofStage::SrsSetup => "srs_setup",
+            ProofStage::Proving => "proving",
+            ProofStage::Verification => "verification",
+            ProofStage::Transaction => "transaction",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Reported to the [`Progress`] callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    Started {
+        stage: ProofStage,
+        at: SystemTime,
+    },
+    Completed {
+        stage: ProofStage,
+        at: SystemTime,
+        duration: Duration,
+    },
+}
+
+impl ProgressEvent {
+    pub fn stage(&self) -> ProofStage {
+        match self {
+            ProgressEvent::Started { stage, .. } | ProgressEvent::Completed { stage, .. } => *stage,
+        }
+    }
+}
+
+type Callback = dyn Fn(ProgressEvent) + Send + Sync;
+
+struct Inner {
+    callback: Box<Callback>,
+    current: Mutex<Option<(ProofStage, Instant)>>,
+}
+
+/// Reports stage changes of a proof run; the default reports nothing
+///
+/// Clones share the current stage, so the prover and an in-process verifier can report to the
+/// same run. Stages only move forward: reporting a stage at or before the current one is ignored.
+#[derive(Clone, Default)]
+pub struct Progress {
+    inner: Option<Arc<Inner>>,
+}
+
+impl Progress {
+    /// Report to `callback`, which is called in order and must not block
+    pub fn new(callback: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            inner: Some(Arc::new(Inner {
+                callback: Box::new(callback),
+                current: Mutex::new(None),
+            })),
+        }
+    }
+
+    /// Complete the current stage and start `stage`
+    pub fn stage(&self, stage: ProofStage) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+
+        let mut current = inner.current.lock().unwrap();
+        if matches!(*current, Some((previous, _)) if previous >= stage) {
+            return;
+        }
+
+        Self::complete(inner, current.take());
+        (inner.callback)(ProgressEvent::Started {
+            stage,
+            at: SystemTime::now(),
+        });
+        *current = Some((stage, Instant::now()));
+    }
+
+    /// Complete the current stage; the run is done
+    pub fn finish(&self) {
+        if let Some(inner) = &self.inner {
+            let mut current = inner.current.lock().unwrap();
+            Self::complete(inner, current.take());
+        }
+    }
+
+    fn complete(inner: &Inner, current: Option<(ProofStage, Instant)>) {
+        if let Some((stage, started)) = current {
+            (inner.callback)(ProgressEvent::Completed {
+                stage,
+                at: SystemTime::now(),
+                duration: started.elapsed(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded() -> (Progress, Arc<Mutex<Vec<ProgressEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let progress = Progress::new(move |event| sink.lock().unwrap().push(event));
+        (progress, events)
+    }
+
+    #[test]
+    fn test_stages_complete_in_order() {
+        let (progress, events) = recorded();
+
+        progress.stage(ProofStage::PreVerification);
+        progress.clone().stage(ProofStage::MpcTlsSetup);
+        // Going back is ignored
+        progress.stage(ProofStage::PreVerification);
+        progress.stage(ProofStage::MpcTlsSetup);
+        progress.finish();
+        progress.finish();
+
+        let events = events.lock().unwrap();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                ProgressEvent::Started { stage, .. } => format!("+{}", stage),
+                ProgressEvent::Completed { stage, .. } => format!("-{}", stage),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                "+pre_verification",
+                "-pre_verification",
+                "+mpc_tls_setup",
+                "-mpc_tls_setup"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_names_match_serde() {
+        let json = serde_json::to_value(ProofStage::SrsSetup).unwrap();
+        assert_eq!(json, ProofStage::SrsSetup.to_string());
+    }
+}
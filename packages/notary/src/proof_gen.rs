@@ -1,6 +1,70 @@
 /// Proof generation module - wraps the prover+verifier flow for API usage
-use crate::prover::prover;
-use crate::types::serialize_public_inputs_for_solidity;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::task::{JoinError, JoinHandle};
+
+use crate::chain::{AlloyChain, ChainClient};
+use crate::error::{NotaryError, Result};
+use crate::inputs::{EthAddress, Nuip, Salt};
+use crate::preflight::preflight;
+use crate::progress::{Progress, ProofStage};
+use crate::proving::ProvingPool;
+use crate::prover::{prover, ProverParams};
+use crate::registry::{resolve_registry_addr, ColombiaRegistry, RegistryAdapter};
+use crate::remote::prove_with_remote_notary;
+use crate::submitter::{ChainSubmitter, Submission, SubmissionMode};
+use crate::tls::TlsTrust;
+use crate::types::{serialize_public_inputs_for_solidity, ZKProofBundle};
+use crate::verifier::VerifierOptions;
+
+/// Configuration for a proof generation run
+#[derive(Clone)]
+pub struct ProofGenConfig {
+    /// Civil registry queried over MPC-TLS
+    pub registry: Arc<dyn RegistryAdapter>,
+    /// Address of a remote notary server (`host:port`); `None` runs the verifier in-process
+    pub notary_addr: Option<String>,
+    /// Root certificates and SPKI pins for the registry connection
+    pub tls: TlsTrust,
+    /// What the notary does with the verified claim
+    pub submission: SubmissionMode,
+    /// Chain used by the pre-flight checks and, with an in-process verifier, for submission
+    pub chain: Arc<dyn ChainClient>,
+    /// Check the testator's registered identity and the trusted server hash before MPC-TLS
+    pub preflight: bool,
+    /// Receives the stages of the run as it goes
+    pub progress: Progress,
+    /// Where proving (and in-process verification) runs, off the async runtime
+    pub proving: ProvingPool,
+}
+
+impl ProofGenConfig {
+    /// Default configuration: Colombian registry as configured by the environment,
+    /// remote notary from `NOTARY_ADDR` if set, TLS trust from [`TlsTrust::from_env`],
+    /// submission from [`SubmissionMode::from_env`], chain from [`AlloyChain::from_env`],
+    /// pre-flight checks unless `PREFLIGHT_CHECKS=false`, no progress reporting, the
+    /// process-wide [`ProvingPool`]
+    pub fn from_env() -> Self {
+        let notary_addr = std::env::var("NOTARY_ADDR").ok().filter(|addr| !addr.is_empty());
+
+        Self {
+            registry: Arc::new(ColombiaRegistry::from_env()),
+            submission: SubmissionMode::from_env(notary_addr.is_some()),
+            notary_addr,
+            tls: TlsTrust::from_env(),
+            chain: Arc::new(AlloyChain::from_env()),
+            preflight: std::env::var("PREFLIGHT_CHECKS")
+                .map(|value| !matches!(value.to_ascii_lowercase().as_str(), "0" | "false" | "no"))
+                .unwrap_or(true),
+            progress: Progress::default(),
+            proving: ProvingPool::global().clone(),
+        }
+    }
+}
 
 /// Result of proof generation
 pub struct ProofGenerationResult {
@@ -8,17 +72,22 @@ pub struct ProofGenerationResult {
     pub proof: Vec<u8>,
     /// Verification key
     pub vk: Vec<u8>,
-    /// Public inputs formatted for Solidity (116 fields)
+    /// Public inputs formatted for Solidity (148 fields)
     pub public_inputs: Vec<String>,
+    /// Receipt, calldata or nothing, depending on [`ProofGenConfig::submission`]
+    pub submission: Submission,
 }
 
 /// Generate a complete ZK proof for death certificate verification
 ///
 /// This function:
 /// 1. Runs pre-verification check against the civil registry API
-/// 2. Executes MPC-TLS prover to get authenticated data
-/// 3. Generates ZK proof using Noir circuit
-/// 4. Serializes public inputs for Solidity
+/// 2. Checks the testator's registered identity and the trusted server hash on-chain
+/// 3. Executes MPC-TLS prover to get authenticated data
+/// 4. Generates ZK proof using Noir circuit
+/// 5. Serializes public inputs for Solidity
+///
+/// Each [`ProofStage`] it goes through is reported to [`ProofGenConfig::progress`].
 ///
 /// # Arguments
 /// * `recipient` - Ethereum address of the heir (20 bytes)
@@ -28,112 +97,182 @@ pub struct ProofGenerationResult {
 /// # Returns
 /// `ProofGenerationResult` containing proof, VK, and public inputs
 pub async fn generate_death_proof(
-    recipient: [u8; 20],
-    nuip: String,
-    salt: [u8; 32],
-    testator_address: [u8; 20],
-) -> anyhow::Result<ProofGenerationResult> {
-    // Configuration for the target server (Civil Registry Mock)
-    // Use environment variable or default to Railway deployment
-    let host = std::env::var("CIVIL_REGISTRY_DOMAIN")
-        .unwrap_or_else(|_| "web-production-05160.up.railway.app".to_string());
-    let port = 443;
-    let server_addr = tokio::net::lookup_host((host.as_str(), port))
-        .await?
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("Failed to lookup host"))?;
-    
-    let uri = std::env::var("CIVIL_REGISTRY_URL")
-        .unwrap_or_else(|_| "https://web-production-05160.up.railway.app/VigenciaCedula/consulta".to_string());
+    recipient: EthAddress,
+    nuip: Nuip,
+    salt: Salt,
+    testator_address: EthAddress,
+) -> Result<ProofGenerationResult> {
+    generate_death_proof_with_config(
+        recipient,
+        nuip,
+        salt,
+        testator_address,
+        &ProofGenConfig::from_env(),
+    )
+    .await
+}
+
+/// Same as [`generate_death_proof`] but against an explicit configuration
+pub async fn generate_death_proof_with_config(
+    recipient: EthAddress,
+    nuip: Nuip,
+    salt: Salt,
+    testator_address: EthAddress,
+    config: &ProofGenConfig,
+) -> Result<ProofGenerationResult> {
+    let registry = config.registry.clone();
+    let progress = &config.progress;
+
+    // The registry must receive the NUIP exactly as it is committed and proven
+    registry.validate_id(&nuip)?;
+    let server_addr = resolve_registry_addr(registry.as_ref()).await?;
 
     tracing::info!("🔍 Running pre-verification check...");
+    progress.stage(ProofStage::PreVerification);
     
     // Pre-verification check to fail fast if subject is alive
-    let client = reqwest::Client::new();
-    let nuip_number: u64 = nuip.parse()
-        .map_err(|_| anyhow::anyhow!("Invalid NUIP format"))?;
+    registry.pre_check(&nuip, &config.tls).await?;
     
-    let res = client.post(&uri)  // Use reference here
-        .json(&serde_json::json!({
-            "nuip": nuip_number,
-            "ip": "143.137.96.53"
-        }))
-        .send()
-        .await?;
+    tracing::info!("✅ Pre-verification passed.");
 
-    if !res.status().is_success() {
-        return Err(anyhow::anyhow!("Failed to query civil registry: {}", res.status()));
+    // Fail fast if ProofHeir would reject the proof anyway
+    if config.preflight {
+        tracing::info!("⛓️ Running on-chain pre-flight checks...");
+        progress.stage(ProofStage::Preflight);
+        preflight(
+            config.chain.as_ref(),
+            registry.as_ref(),
+            testator_address,
+            &nuip,
+            salt,
+        )
+        .await?;
+        tracing::info!("✅ Identity registered and server hash trusted on-chain.");
     }
 
-    let response_data: serde_json::Value = res.json().await?;
-    tracing::info!("Server response: {:?}", response_data);
+    tracing::info!("Starting MPC-TLS...");
 
-    if let Some(vigencia) = response_data.get("vigencia").and_then(|v| v.as_str()) {
-        if vigencia == "Vigente (Vivo)" {
-            tracing::error!("❌ Pre-verification FAILED: Subject is 'Vigente (Vivo)'");
-            anyhow::bail!("Cannot generate 'Proof of Death' - subject is alive");
+    let (proof_bundle, submission) = match &config.notary_addr {
+        Some(notary_addr) => {
+            tracing::info!("🌐 Using remote notary at {}", notary_addr);
+            let params = ProverParams {
+                server_addr,
+                registry: registry.as_ref(),
+                tls: &config.tls,
+                recipient,
+                nuip: &nuip,
+                salt,
+                progress,
+                proving: &config.proving,
+            };
+            prove_with_remote_notary(notary_addr, &params, testator_address, config.submission)
+                .await?
         }
-    }
-    
-    tracing::info!("✅ Pre-verification passed. Starting MPC-TLS...");
+        None => prove_with_local_notary(config, server_addr, recipient, nuip, salt, testator_address).await?,
+    };
+
+    tracing::info!("📦 Received proof bundle:");
+    tracing::info!("   VK size: {} bytes", proof_bundle.vk.len());
+    tracing::info!("   Proof size: {} bytes", proof_bundle.proof.len());
+    tracing::info!("   Recipient: 0x{}", hex::encode(&proof_bundle.public_inputs.recipient));
+    tracing::info!("   Server hash: {}", hex::encode(&proof_bundle.public_inputs.server_hash));
+    tracing::info!("   ID commitment: {}", hex::encode(&proof_bundle.public_inputs.id_commitment));
+    tracing::info!("   Status commitment: {}", hex::encode(&proof_bundle.public_inputs.status_commitment));
+    tracing::info!("   NUIP commitment: {}", hex::encode(&proof_bundle.public_inputs.nuip_commitment));
+
+    // Use the public inputs directly from the proof bundle
+    // These are the EXACT values that were used to generate the proof
+    let public_inputs = serialize_public_inputs_for_solidity(&proof_bundle.public_inputs);
+    progress.finish();
+
+    Ok(ProofGenerationResult {
+        proof: proof_bundle.proof,
+        vk: proof_bundle.vk,
+        public_inputs,
+        submission,
+    })
+}
+
+/// Run prover and verifier in this process over in-memory channels
+async fn prove_with_local_notary(
+    config: &ProofGenConfig,
+    server_addr: SocketAddr,
+    recipient: EthAddress,
+    nuip: Nuip,
+    salt: Salt,
+    testator_address: EthAddress,
+) -> Result<(ZKProofBundle, Submission)> {
+    let registry = config.registry.clone();
+    let tls = config.tls.clone();
+    let progress = config.progress.clone();
+    let proving = config.proving.clone();
 
     // Create duplex channels for prover-verifier communication
     let (prover_socket, verifier_socket) = tokio::io::duplex(1 << 23);
     let (prover_extra_socket, verifier_extra_socket) = tokio::io::duplex(1 << 23);
 
-    // Clone nuip before moving into spawn
-    let nuip_for_prover = nuip.clone();
-
     // Spawn prover task
-    let prover_handle = tokio::spawn(async move {
-        prover(
-            prover_socket,
-            prover_extra_socket,
-            &server_addr,
-            &uri,
+    let prover_handle = AbortOnDrop(tokio::spawn(async move {
+        let params = ProverParams {
+            server_addr,
+            registry: registry.as_ref(),
+            tls: &tls,
             recipient,
-            &nuip_for_prover,
+            nuip: &nuip,
             salt,
-        ).await
-        .map_err(|e| anyhow::anyhow!("Prover error: {}", e))
-    });
+            progress: &progress,
+            proving: &proving,
+        };
+        prover(prover_socket, prover_extra_socket, &params).await
+    }));
 
     // Spawn verifier task - NOW WITH TESTATOR ADDRESS
-    let verifier_handle = tokio::spawn(async move {
-        crate::verifier::verifier(verifier_socket, verifier_extra_socket, testator_address)
-            .await
-            .map_err(|e| anyhow::anyhow!("Verifier error: {}", e))
-    });
+    let verifier_options = VerifierOptions {
+        registry: config.registry.clone(),
+        tls: config.tls.clone(),
+        submission: config.submission,
+        submitter: Arc::new(ChainSubmitter::new(config.chain.clone())),
+        progress: config.progress.clone(),
+        proving: config.proving.clone(),
+        ..VerifierOptions::from_env()
+    };
+    let verifier_handle = AbortOnDrop(tokio::spawn(async move {
+        crate::verifier::verifier(
+            verifier_socket,
+            verifier_extra_socket,
+            testator_address.into(),
+            &verifier_options,
+        )
+        .await
+    }));
 
     // Wait for both tasks
-    let (prover_result, verifier_result) = tokio::try_join!(prover_handle, verifier_handle)?;
+    let (prover_result, verifier_result) =
+        tokio::try_join!(prover_handle, verifier_handle).map_err(NotaryError::internal)?;
     
     // Get the proof bundle from prover
     let proof_bundle = prover_result?;
     
     // Verify that verifier completed successfully
-    let _transcript = verifier_result?;
+    let session = verifier_result?;
 
-    tracing::info!("📦 Received proof bundle:");
-    tracing::info!("   VK size: {} bytes", proof_bundle.vk.len());
-    tracing::info!("   Proof size: {} bytes", proof_bundle.proof.len());
-    tracing::info!("   Recipient: 0x{}", hex::encode(&proof_bundle.public_inputs.recipient));
-    tracing::info!("   Server hash: {}", hex::encode(&proof_bundle.public_inputs.server_hash));
-    tracing::info!("   ID commitment: {}", hex::encode(&proof_bundle.public_inputs.id_commitment));
-    tracing::info!("   Status commitment: {}", hex::encode(&proof_bundle.public_inputs.status_commitment));
+    Ok((proof_bundle, session.submission))
+}
 
-    // Use the public inputs directly from the proof bundle
-    // These are the EXACT values that were used to generate the proof
-    let public_inputs = serialize_public_inputs_for_solidity(
-        proof_bundle.public_inputs.recipient,
-        proof_bundle.public_inputs.server_hash,
-        proof_bundle.public_inputs.id_commitment,
-        &proof_bundle.public_inputs.status_commitment,
-    ).map_err(|e| anyhow::anyhow!("Failed to serialize public inputs: {}", e))?;
+/// Aborts the task when dropped, so cancelling a proof run also stops its prover and verifier
+/// (and with it any pending on-chain submission)
+pub(crate) struct AbortOnDrop<T>(pub(crate) JoinHandle<T>);
 
-    Ok(ProofGenerationResult {
-        proof: proof_bundle.proof,
-        vk: proof_bundle.vk,
-        public_inputs,
-    })
+impl<T> Future for AbortOnDrop<T> {
+    type Output = std::result::Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
@@ -0,0 +1,322 @@
+/// Proof layout - splits noir-rs UltraHonk proof bytes into public inputs and proof body
+use serde_json::Value;
+
+use crate::error::{NotaryError, Result};
+
+/// Size of one BN254 field element in a serialized proof
+pub const FIELD_SIZE: usize = 32;
+
+/// Sumcheck rounds every proof is padded to, whatever the circuit size
+/// (`CONST_PROOF_SIZE_LOG_N` in `packages/contracts/src/Verifier.sol`)
+pub const CONST_PROOF_SIZE_LOG_N: usize = 28;
+
+/// Pairing point fields the prover appends to the circuit's public inputs
+/// (`PAIRING_POINTS_SIZE` in Verifier.sol); the VK counts them as public inputs
+pub const PAIRING_POINTS_FIELDS: usize = 16;
+
+/// Polynomials evaluated in sumcheck (`NUMBER_OF_ENTITIES` in Verifier.sol)
+const NUMBER_OF_ENTITIES: usize = 41;
+
+/// Evaluations per ZK sumcheck univariate (`ZK_BATCHED_RELATION_PARTIAL_LENGTH` in Verifier.sol)
+const ZK_BATCHED_RELATION_PARTIAL_LENGTH: usize = 9;
+
+/// Fields of a G1 point in a proof (x and y, two limbs each)
+const G1_PROOF_POINT_FIELDS: usize = 4;
+
+/// Fields in a ZK UltraHonk (keccak) proof body, as `loadProof` in Verifier.sol reads it
+/// MUST match `PROOF_SIZE` in `packages/contracts/src/Verifier.sol` (checked by a test)
+pub const ULTRA_HONK_ZK_PROOF_FIELDS: usize = zk_proof_fields(CONST_PROOF_SIZE_LOG_N);
+
+const fn zk_proof_fields(log_n: usize) -> usize {
+    PAIRING_POINTS_FIELDS
+        // w1, w2, w3, lookup read counts/tags, w4, lookup inverses, z_perm, first libra commitment
+        + 9 * G1_PROOF_POINT_FIELDS
+        // libra sum, sumcheck univariates and evaluations, libra evaluation
+        + 1
+        + ZK_BATCHED_RELATION_PARTIAL_LENGTH * log_n
+        + NUMBER_OF_ENTITIES
+        + 1
+        // two more libra commitments, gemini masking polynomial and its evaluation
+        + 3 * G1_PROOF_POINT_FIELDS
+        + 1
+        // gemini fold commitments and evaluations, libra polynomial evaluations
+        + (log_n - 1) * G1_PROOF_POINT_FIELDS
+        + log_n
+        + 4
+        // shplonk Q and KZG quotient
+        + 2 * G1_PROOF_POINT_FIELDS
+}
+
+/// Bytes of the VK header: log circuit size, public inputs, public inputs offset (u64 BE each)
+const VK_HEADER_LEN: usize = 24;
+
+/// Bytes of one G1 commitment in the VK
+const VK_COMMITMENT_LEN: usize = 64;
+
+/// Layout of `prove_ultra_honk_keccak` output: `public_inputs || proof_body`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofLayout {
+    public_input_fields: usize,
+    proof_fields: usize,
+}
+
+/// A proof split at the public-input boundary
+#[derive(Debug, Clone, Copy)]
+pub struct SplitProof<'a> {
+    /// Public inputs, one 32-byte field each
+    pub public_inputs: &'a [u8],
+    /// Proof body as consumed by the Solidity verifier
+    pub body: &'a [u8],
+}
+
+impl ProofLayout {
+    /// Layout for a compiled Noir program (`circuits.json`)
+    pub fn from_program_json(program: &Value) -> Result<Self> {
+        let abi = program
+            .get("abi")
+            .ok_or_else(|| NotaryError::zk("abi field not found in program.json"))?;
+        Self::from_abi(abi)
+    }
+
+    /// Layout from a Noir ABI: counts the fields of every public parameter and the return value
+    pub fn from_abi(abi: &Value) -> Result<Self> {
+        let parameters = abi
+            .get("parameters")
+            .and_then(|p| p.as_array())
+            .ok_or_else(|| NotaryError::zk("ABI has no parameters array"))?;
+
+        let mut public_input_fields = 0;
+        for parameter in parameters {
+            if parameter.get("visibility").and_then(|v| v.as_str()) == Some("public") {
+                public_input_fields += abi_type_fields(&parameter["type"])?;
+            }
+        }
+
+        // Return values of `main` are public inputs too
+        if let Some(return_type) = abi.get("return_type").filter(|r| !r.is_null()) {
+            public_input_fields += abi_type_fields(&return_type["abi_type"])?;
+        }
+
+        Ok(Self {
+            public_input_fields,
+            proof_fields: ULTRA_HONK_ZK_PROOF_FIELDS,
+        })
+    }
+
+    /// Number of public input fields the circuit exposes
+    pub fn public_input_fields(&self) -> usize {
+        self.public_input_fields
+    }
+
+    /// Exact length in bytes of a proof with its public inputs prepended
+    pub fn expected_len(&self) -> usize {
+        (self.public_input_fields + self.proof_fields) * FIELD_SIZE
+    }
+
+    /// Check a verification key (`get_ultra_honk_verification_key`) describes proofs of this layout
+    ///
+    /// The VK must count exactly this layout's public inputs plus the pairing points, and the
+    /// circuit must fit in the [`CONST_PROOF_SIZE_LOG_N`] rounds the proof body is sized for.
+    pub fn check_vk(&self, vk: &[u8]) -> Result<()> {
+        if vk.len() < VK_HEADER_LEN || (vk.len() - VK_HEADER_LEN) % VK_COMMITMENT_LEN != 0 {
+            return Err(NotaryError::zk(format!(
+                "Unexpected verification key size: {} bytes",
+                vk.len()
+            )));
+        }
+        let header = |i: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&vk[i * 8..(i + 1) * 8]);
+            u64::from_be_bytes(bytes)
+        };
+
+        // The pinned Barretenberg writes the log of the circuit size; any other value is not a
+        // key for proofs padded to CONST_PROOF_SIZE_LOG_N rounds
+        let log_n = header(0);
+        if log_n == 0 || log_n as usize > CONST_PROOF_SIZE_LOG_N {
+            return Err(NotaryError::zk(format!(
+                "Verification key log circuit size {} is outside 1..={}",
+                log_n, CONST_PROOF_SIZE_LOG_N
+            )));
+        }
+
+        let vk_public_inputs = header(1) as usize;
+        if vk_public_inputs != self.public_input_fields + PAIRING_POINTS_FIELDS {
+            return Err(NotaryError::zk(format!(
+                "Verification key has {} public inputs, expected {} ({} circuit fields + {} pairing points)",
+                vk_public_inputs,
+                self.public_input_fields + PAIRING_POINTS_FIELDS,
+                self.public_input_fields,
+                PAIRING_POINTS_FIELDS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Split a proof into public inputs and body, rejecting any unexpected size
+    pub fn split<'a>(&self, proof: &'a [u8]) -> Result<SplitProof<'a>> {
+        if proof.len() != self.expected_len() {
+            return Err(NotaryError::zk(format!(
+                "Unexpected proof size: {} bytes, expected {} ({} public input fields + {} proof fields of {} bytes)",
+                proof.len(),
+                self.expected_len(),
+                self.public_input_fields,
+                self.proof_fields,
+                FIELD_SIZE
+            )));
+        }
+
+        let (public_inputs, body) = proof.split_at(self.public_input_fields * FIELD_SIZE);
+        Ok(SplitProof {
+            public_inputs,
+            body,
+        })
+    }
+}
+
+/// Number of field elements an ABI type occupies
+fn abi_type_fields(abi_type: &Value) -> Result<usize> {
+    let kind = abi_type
+        .get("kind")
+        .and_then(|k| k.as_str())
+        .ok_or_else(|| NotaryError::zk(format!("ABI type without kind: {}", abi_type)))?;
+
+    let length = || {
+        abi_type
+            .get("length")
+            .and_then(|l| l.as_u64())
+            .map(|l| l as usize)
+            .ok_or_else(|| NotaryError::zk(format!("ABI {} without length", kind)))
+    };
+
+    match kind {
+        "field" | "integer" | "boolean" => Ok(1),
+        "string" => length(),
+        "array" => Ok(length()? * abi_type_fields(&abi_type["type"])?),
+        "tuple" => abi_type["fields"]
+            .as_array()
+            .ok_or_else(|| NotaryError::zk("ABI tuple without fields"))?
+            .iter()
+            .map(abi_type_fields)
+            .sum(),
+        "struct" => abi_type["fields"]
+            .as_array()
+            .ok_or_else(|| NotaryError::zk("ABI struct without fields"))?
+            .iter()
+            .map(|field| abi_type_fields(&field["type"]))
+            .sum(),
+        other => Err(NotaryError::zk(format!("Unsupported ABI type kind: {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn byte_array(length: usize) -> Value {
+        json!({ "kind": "array", "length": length, "type": { "kind": "integer", "sign": "unsigned", "width": 8 } })
+    }
+
+    fn sample_abi() -> Value {
+        json!({
+            "parameters": [
+                { "name": "recipient", "type": byte_array(20), "visibility": "public" },
+                { "name": "server_hash", "type": byte_array(32), "visibility": "public" },
+                { "name": "nuip", "type": { "kind": "string", "length": 15 }, "visibility": "private" },
+                { "name": "status_commitment", "type": byte_array(32), "visibility": "public" }
+            ],
+            "return_type": null
+        })
+    }
+
+    #[test]
+    fn test_public_input_count_from_abi() {
+        let layout = ProofLayout::from_abi(&sample_abi()).unwrap();
+        assert_eq!(layout.public_input_fields(), 84);
+        assert_eq!(layout.expected_len(), (84 + ULTRA_HONK_ZK_PROOF_FIELDS) * FIELD_SIZE);
+    }
+
+    fn vk(log_n: u64, public_inputs: u64) -> Vec<u8> {
+        let mut vk = Vec::new();
+        vk.extend_from_slice(&log_n.to_be_bytes());
+        vk.extend_from_slice(&public_inputs.to_be_bytes());
+        vk.extend_from_slice(&0u64.to_be_bytes());
+        vk.extend_from_slice(&[0u8; 28 * VK_COMMITMENT_LEN]);
+        vk
+    }
+
+    #[test]
+    fn test_check_vk() {
+        let layout = ProofLayout::from_abi(&sample_abi()).unwrap();
+        assert!(layout.check_vk(&vk(16, 84 + 16)).is_ok());
+        // A circuit size instead of its log is not a key this Barretenberg writes
+        assert!(layout.check_vk(&vk(1 << 16, 84 + 16)).is_err());
+        assert!(layout.check_vk(&vk(0, 84 + 16)).is_err());
+
+        // Public inputs of another circuit build, without pairing points, or too many gates
+        assert!(layout.check_vk(&vk(16, 116 + 16)).is_err());
+        assert!(layout.check_vk(&vk(16, 84)).is_err());
+        assert!(layout.check_vk(&vk(29, 84 + 16)).is_err());
+        assert!(layout.check_vk(&vk(16, 84 + 16)[..100]).is_err());
+    }
+
+    /// Reads a `uint256 constant NAME = value;` from the Solidity verifier
+    fn verifier_constant(verifier: &str, name: &str) -> usize {
+        let pattern = format!("uint256 constant {} = ", name);
+        let start = verifier
+            .find(&pattern)
+            .unwrap_or_else(|| panic!("{} not found in Verifier.sol", name))
+            + pattern.len();
+        let end = start + verifier[start..].find(';').unwrap();
+        verifier[start..end].trim().parse().unwrap()
+    }
+
+    #[test]
+    fn test_proof_size_matches_solidity_verifier() {
+        let verifier = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../contracts/src/Verifier.sol"
+        ))
+        .unwrap();
+
+        assert_eq!(verifier_constant(&verifier, "PROOF_SIZE"), ULTRA_HONK_ZK_PROOF_FIELDS);
+        assert_eq!(
+            verifier_constant(&verifier, "CONST_PROOF_SIZE_LOG_N"),
+            CONST_PROOF_SIZE_LOG_N
+        );
+        assert_eq!(
+            verifier_constant(&verifier, "PAIRING_POINTS_SIZE"),
+            PAIRING_POINTS_FIELDS
+        );
+        assert_eq!(
+            verifier_constant(&verifier, "NUMBER_OF_ENTITIES"),
+            NUMBER_OF_ENTITIES
+        );
+        assert_eq!(
+            verifier_constant(&verifier, "ZK_BATCHED_RELATION_PARTIAL_LENGTH"),
+            ZK_BATCHED_RELATION_PARTIAL_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_split_exact_size() {
+        let layout = ProofLayout::from_abi(&sample_abi()).unwrap();
+        let proof = vec![7u8; layout.expected_len()];
+
+        let split = layout.split(&proof).unwrap();
+        assert_eq!(split.public_inputs.len(), 84 * FIELD_SIZE);
+        assert_eq!(split.body.len(), ULTRA_HONK_ZK_PROOF_FIELDS * FIELD_SIZE);
+    }
+
+    #[test]
+    fn test_split_rejects_unexpected_size() {
+        let layout = ProofLayout::from_abi(&sample_abi()).unwrap();
+
+        // Body only (public inputs not prepended) and one extra field
+        let body_only = vec![0u8; ULTRA_HONK_ZK_PROOF_FIELDS * FIELD_SIZE];
+        let too_long = vec![0u8; layout.expected_len() + FIELD_SIZE];
+        assert!(layout.split(&body_only).is_err());
+        assert!(layout.split(&too_long).is_err());
+    }
+}
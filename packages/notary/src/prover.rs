@@ -1,6 +1,14 @@
 use std::net::SocketAddr;
 
-use crate::types::received_commitments;
+use crate::circuit::CircuitContext;
+use crate::commitment::id_commitment;
+use crate::error::{NotaryError, Result};
+use crate::inputs::{EthAddress, Nuip, RegistryDomain, Salt};
+use crate::progress::{Progress, ProofStage};
+use crate::proving::ProvingPool;
+use crate::registry::RegistryAdapter;
+use crate::tls::TlsTrust;
+use crate::types::{received_commitments, sent_commitments};
 
 use super::types::ZKProofBundle;
 
@@ -8,19 +16,13 @@ use http_body_util::Full;
 use hyper::{body::Bytes, Request, StatusCode, Uri};
 use hyper_util::rt::TokioIo;
 use k256::sha2::{Digest, Sha256};
-use serde_json::Value;
-use noir::{
-    barretenberg::{
-        prove::prove_ultra_honk_keccak, srs::setup_srs_from_bytecode, verify::get_ultra_honk_verification_key,
-    },
-    witness::from_vec_str_to_witness_map,
-};
+use noir::{barretenberg::prove::prove_ultra_honk_keccak, witness::from_vec_str_to_witness_map};
 use spansy::{
-    http::{BodyContent, Responses},
+    http::{BodyContent, Requests, Responses},
     Spanned,
 };
 use tlsn::{
-    config::{CertificateDer, ProtocolConfig, RootCertStore},
+    config::ProtocolConfig,
     connection::ServerName,
     hash::HashAlgId,
     prover::{ProveConfig, ProveConfigBuilder, Prover, ProverConfig, TlsConfig},
@@ -40,65 +42,96 @@ use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::compat::{FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
 use tracing::instrument;
 
-#[instrument(skip(verifier_socket, verifier_extra_socket))]
+/// What the prover proves and where: everything [`prover`] needs besides the verifier sockets
+#[derive(Clone, Copy)]
+pub struct ProverParams<'a> {
+    /// Resolved address of the registry's TLS server
+    pub server_addr: SocketAddr,
+    /// Registry queried over MPC-TLS
+    pub registry: &'a dyn RegistryAdapter,
+    /// Roots and SPKI pins the registry's certificate is checked against
+    pub tls: &'a TlsTrust,
+    /// Heir the proof designates
+    pub recipient: EthAddress,
+    pub nuip: &'a Nuip,
+    pub salt: Salt,
+    pub progress: &'a Progress,
+    /// Where the UltraHonk proof runs, off the async runtime
+    pub proving: &'a ProvingPool,
+}
+
+#[instrument(skip_all, fields(server_addr = %params.server_addr, recipient = ?params.recipient))]
 pub async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
     verifier_socket: T,
     mut verifier_extra_socket: T,
-    server_addr: &SocketAddr,
-    uri: &str,
-    recipient: [u8; 20],
-    nuip: &str,
-    salt: [u8; 32],
-) -> Result<ZKProofBundle, Box<dyn std::error::Error>> {
-    let uri = uri.parse::<Uri>()?;
+    params: &ProverParams<'_>,
+) -> Result<ZKProofBundle> {
+    let ProverParams {
+        server_addr,
+        registry,
+        tls,
+        recipient,
+        nuip,
+        salt,
+        progress,
+        proving,
+    } = *params;
+
+    let uri = registry.url().parse::<Uri>().map_err(NotaryError::invalid_input)?;
 
     if uri.scheme().map(|s| s.as_str()) != Some("https") {
-        return Err("URI must use HTTPS scheme".into());
+        return Err(NotaryError::invalid_input("URI must use HTTPS scheme"));
     }
 
-    let server_domain = uri.authority().ok_or("URI must have authority")?.host();
+    let server_domain = registry.domain()?;
+
+    // Trusted roots: configured CA bundles and/or native roots
+    let tls_roots = tls.root_store()?;
 
-    // Load native root certificates and convert to tlsn format
-    let native_certs = rustls_native_certs::load_native_certs()
-        .map_err(|e| format!("Failed to load platform certificates: {}", e))?;
-    
-    let roots: Vec<CertificateDer> = native_certs
-        .into_iter()
-        .map(|cert| CertificateDer(cert.0))
-        .collect();
-    
-    let tls_roots = RootCertStore { roots };
-    
     let mut tls_config_builder = TlsConfig::builder();
     tls_config_builder.root_store(tls_roots);
-    let tls_config = tls_config_builder.build()?;
+    let tls_config = tls_config_builder.build().map_err(NotaryError::mpc_tls)?;
 
     // Set up protocol configuration for prover.
     let mut prover_config_builder = ProverConfig::builder();
     prover_config_builder
-        .server_name(ServerName::Dns(server_domain.try_into()?))
+        .server_name(ServerName::Dns(
+            server_domain
+                .as_str()
+                .try_into()
+                .map_err(NotaryError::invalid_input)?,
+        ))
         .tls_config(tls_config)
         .protocol_config(
             ProtocolConfig::builder()
                 .max_sent_data(MAX_SENT_DATA)
                 .max_recv_data(MAX_RECV_DATA)
-                .build()?,
+                .build()
+                .map_err(NotaryError::mpc_tls)?,
         );
 
-    let prover_config = prover_config_builder.build()?;
+    let prover_config = prover_config_builder.build().map_err(NotaryError::mpc_tls)?;
 
     // Create prover and connect to verifier.
     //
     // Perform the setup phase with the verifier.
+    progress.stage(ProofStage::MpcTlsSetup);
     let prover = Prover::new(prover_config)
         .setup(verifier_socket.compat())
-        .await?;
+        .await
+        .map_err(NotaryError::mpc_tls)?;
 
     // Connect to TLS Server.
-    let tls_client_socket = tokio::net::TcpStream::connect(server_addr).await?;
+    progress.stage(ProofStage::HttpRequest);
+    let tls_client_socket = tokio::net::TcpStream::connect(server_addr)
+        .await
+        .map_err(NotaryError::registry)?;
 
     // Pass server connection into the prover.
-    let (mpc_tls_connection, prover_fut) = prover.connect(tls_client_socket.compat()).await?;
+    let (mpc_tls_connection, prover_fut) = prover
+        .connect(tls_client_socket.compat())
+        .await
+        .map_err(NotaryError::mpc_tls)?;
 
     // Wrap the connection in a TokioIo compatibility layer to use it with hyper.
     let mpc_tls_connection = TokioIo::new(mpc_tls_connection.compat());
@@ -108,36 +141,55 @@ pub async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
 
     // MPC-TLS Handshake.
     let (mut request_sender, connection) =
-        hyper::client::conn::http1::handshake(mpc_tls_connection).await?;
+        hyper::client::conn::http1::handshake(mpc_tls_connection)
+            .await
+            .map_err(NotaryError::mpc_tls)?;
 
     // Spawn the connection to run in the background.
     tokio::spawn(connection);
 
     // MPC-TLS: Send Request and wait for Response.
-    let nuip_number: u64 = nuip.parse().map_err(|_| "Invalid NUIP format")?;
-    let payload = serde_json::json!({
-        "nuip": nuip_number,
-        "ip": "143.137.96.53"
-    });
+    let payload = registry.request_body(nuip)?;
     let payload_bytes = Bytes::from(payload.to_string());
 
     let request = Request::builder()
         .uri(uri.clone())
-        .header("Host", server_domain)
+        .header("Host", server_domain.as_str())
         .header("Connection", "close")
         .header("Content-Type", "application/json")
         .method("POST")
-        .body(Full::new(payload_bytes))?;
+        .body(Full::new(payload_bytes))
+        .map_err(NotaryError::invalid_input)?;
 
-    let response = request_sender.send_request(request).await?;
+    let response = request_sender
+        .send_request(request)
+        .await
+        .map_err(NotaryError::mpc_tls)?;
 
     if response.status() != StatusCode::OK {
-        return Err(format!("MPC-TLS request failed with status {}", response.status()).into());
+        return Err(NotaryError::registry(format!(
+            "MPC-TLS request failed with status {}",
+            response.status()
+        )));
     }
 
     // Create proof for the Verifier.
-    let mut prover = prover_task.await??;
+    let mut prover = prover_task
+        .await
+        .map_err(NotaryError::internal)?
+        .map_err(NotaryError::mpc_tls)?;
+
+    // Refuse to prove anything about a server whose key is not pinned
+    if !tls.spki_pins.is_empty() {
+        let leaf = prover
+            .tls_transcript()
+            .server_cert_chain()
+            .and_then(|chain| chain.first())
+            .ok_or_else(|| NotaryError::mpc_tls("Server certificate chain not available for pinning"))?;
+        tls.check_pins(&leaf.0)?;
+    }
 
+    progress.stage(ProofStage::Commitment);
     let transcript = prover.transcript().clone();
     let mut prove_config_builder = ProveConfig::builder(&transcript);
 
@@ -150,15 +202,19 @@ pub async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
     let recv_len = received.len();
     tracing::info!("Sent length: {}, Received length: {}", sent_len, recv_len);
 
-    // Reveal the entire HTTP request 
-    // Simplified: reveal all sent data as public
-    prove_config_builder.reveal_sent(&(0..sent_len))?;
-
-    // Create hash commitment for the 'vigencia' status field
+    // Create hash commitments for the national ID (sent) and the 'vigencia' status field (received)
     let mut transcript_commitment_builder = TranscriptCommitConfig::builder(&transcript);
     transcript_commitment_builder.default_kind(TranscriptCommitmentKind::Hash {
         alg: HashAlgId::SHA256,
     });
+
+    // Reveal the HTTP request except the national ID, which is only committed
+    reveal_sent(
+        sent,
+        registry.id_field(),
+        &mut prove_config_builder,
+        &mut transcript_commitment_builder,
+    )?;
     
     // Reveal everything received except (optionally) the status if we want to keep it private-ish?
     // Actually, in the ZK circuit, the status is a Private Input, but the status_commitment is Public.
@@ -168,70 +224,154 @@ pub async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
     
     reveal_received(
         received,
+        registry.status_field(),
         &mut prove_config_builder,
         &mut transcript_commitment_builder,
     )?;
 
-    let transcripts_commitment_config = transcript_commitment_builder.build()?;
+    let transcripts_commitment_config = transcript_commitment_builder
+        .build()
+        .map_err(NotaryError::mpc_tls)?;
     prove_config_builder.transcript_commit(transcripts_commitment_config);
 
-    let prove_config = prove_config_builder.build()?;
+    let prove_config = prove_config_builder.build().map_err(NotaryError::mpc_tls)?;
 
     // MPC-TLS prove
-    let prover_output = prover.prove(&prove_config).await?;
-    prover.close().await?;
+    let prover_output = prover
+        .prove(&prove_config)
+        .await
+        .map_err(NotaryError::mpc_tls)?;
+    prover.close().await.map_err(NotaryError::mpc_tls)?;
 
     // Prepare inputs for Noir
     let received_commitments = received_commitments(&prover_output.transcript_commitments);
     let received_commitment = received_commitments
         .first()
-        .ok_or("No received commitments found (status)")?; 
+        .ok_or_else(|| NotaryError::commitment("No received commitments found (status)"))?;
         
-    let received_secrets = received_secrets(&prover_output.transcript_secrets);
+    let received_secrets = hash_secrets(&prover_output.transcript_secrets, Direction::Received);
     let received_secret = received_secrets
         .first()
-        .ok_or("No received secrets found (blinder)")?; 
+        .ok_or_else(|| NotaryError::commitment("No received secrets found (blinder)"))?;
+
+    let sent_commitments = sent_commitments(&prover_output.transcript_commitments);
+    let sent_commitment = sent_commitments
+        .first()
+        .ok_or_else(|| NotaryError::commitment("No sent commitments found (nuip)"))?;
+
+    let sent_secrets = hash_secrets(&prover_output.transcript_secrets, Direction::Sent);
+    let sent_secret = sent_secrets
+        .first()
+        .ok_or_else(|| NotaryError::commitment("No sent secrets found (nuip blinder)"))?;
+
+    let status_opening = open_commitment(received, received_commitment, received_secret)?;
+    let nuip_opening = open_commitment(sent, sent_commitment, sent_secret)?;
 
     // Use values passed as parameters from client
     let proof_input = prepare_zk_proof_input(
-        received, 
-        received_commitment, 
-        received_secret,
+        status_opening,
+        nuip_opening,
         recipient,
         server_domain,
         nuip,
         salt
     )?;
     
-    let proof_bundle = generate_zk_proof(&proof_input)?;
+    let proof_bundle = generate_zk_proof(&proof_input, progress, proving).await?;
 
     // Send zk proof bundle to verifier
-    let serialized_proof = bincode::serialize(&proof_bundle)?;
-    verifier_extra_socket.write_all(&serialized_proof).await?;
-    verifier_extra_socket.shutdown().await?;
+    let serialized_proof = bincode::serialize(&proof_bundle).map_err(NotaryError::internal)?;
+    verifier_extra_socket
+        .write_all(&serialized_proof)
+        .await
+        .map_err(NotaryError::mpc_tls)?;
+    verifier_extra_socket
+        .shutdown()
+        .await
+        .map_err(NotaryError::mpc_tls)?;
+    progress.stage(ProofStage::Verification);
 
     // Return the proof bundle for API usage
     Ok(proof_bundle)
 }
 
+/// Reveals the request line, headers and JSON structure of the sent request,
+/// but only commits to the value of `id_field` so the national ID stays private.
+fn reveal_sent(
+    sent: &[u8],
+    id_field: &str,
+    builder: &mut ProveConfigBuilder<'_>,
+    transcript_commitment_builder: &mut TranscriptCommitConfigBuilder,
+) -> Result<()> {
+    let reqs = Requests::new_from_slice(sent)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(NotaryError::mpc_tls)?;
+
+    let request = reqs
+        .first()
+        .ok_or_else(|| NotaryError::mpc_tls("No requests found"))?;
+    let body = request
+        .body
+        .as_ref()
+        .ok_or_else(|| NotaryError::mpc_tls("Request body not found"))?;
+
+    let BodyContent::Json(json) = &body.content else {
+        return Err(NotaryError::mpc_tls("Expected JSON request body"));
+    };
+
+    let id_value = json.get(id_field).ok_or_else(|| {
+        NotaryError::mpc_tls(format!("{} field not found in request JSON", id_field))
+    })?;
+
+    let indices = id_value.span().indices();
+    let start = indices
+        .min()
+        .ok_or_else(|| NotaryError::mpc_tls("Empty national ID span"))?;
+    let end = indices
+        .end()
+        .ok_or_else(|| NotaryError::mpc_tls("Empty national ID span"))?;
+
+    // Everything before and after the ID is public (method, path, Host, JSON keys)
+    builder
+        .reveal_sent(&(0..start))
+        .map_err(NotaryError::mpc_tls)?;
+    builder
+        .reveal_sent(&(end..sent.len()))
+        .map_err(NotaryError::mpc_tls)?;
+
+    transcript_commitment_builder
+        .commit_sent(&(start..end))
+        .map_err(NotaryError::mpc_tls)?;
+
+    Ok(())
+}
+
 fn reveal_received(
     received: &[u8],
+    status_field: &str,
     _builder: &mut ProveConfigBuilder<'_>,
     transcript_commitment_builder: &mut TranscriptCommitConfigBuilder,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let resp = Responses::new_from_slice(received).collect::<Result<Vec<_>, _>>()?;
+) -> Result<()> {
+    let resp = Responses::new_from_slice(received)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(NotaryError::mpc_tls)?;
 
-    let response = resp.first().ok_or("No responses found")?;
-    let body = response.body.as_ref().ok_or("Response body not found")?;
+    let response = resp
+        .first()
+        .ok_or_else(|| NotaryError::mpc_tls("No responses found"))?;
+    let body = response
+        .body
+        .as_ref()
+        .ok_or_else(|| NotaryError::mpc_tls("Response body not found"))?;
 
     let BodyContent::Json(json) = &body.content else {
-        return Err("Expected JSON body content".into());
+        return Err(NotaryError::mpc_tls("Expected JSON body content"));
     };
 
-    // Locate "vigencia" field
-    let vigencia = json
-        .get("vigencia")
-        .ok_or("vigencia field not found in JSON")?;
+    // Locate the registry's status field (e.g. "vigencia")
+    let vigencia = json.get(status_field).ok_or_else(|| {
+        NotaryError::mpc_tls(format!("{} field not found in JSON", status_field))
+    })?;
 
     // We do NOT reveal the value of vigencia here if we want it to be private input to ZK.
     // We only COMMIT to it.
@@ -243,118 +383,142 @@ fn reveal_received(
     // Commit to the value of vigencia
     // The value span includes quotes for strings? Check spansy behavior. 
     // Usually json.get returns the value node. 
-    transcript_commitment_builder.commit_recv(vigencia.span())?;
+    transcript_commitment_builder
+        .commit_recv(vigencia.span())
+        .map_err(NotaryError::mpc_tls)?;
 
     Ok(())
 }
 
-// extract secret from prover output
-fn received_secrets(transcript_secrets: &[TranscriptSecret]) -> Vec<&PlaintextHashSecret> {
+// extract secrets for one direction from prover output
+fn hash_secrets(
+    transcript_secrets: &[TranscriptSecret],
+    direction: Direction,
+) -> Vec<&PlaintextHashSecret> {
     transcript_secrets
         .iter()
         .filter_map(|secret| match secret {
-            TranscriptSecret::Hash(hash) if hash.direction == Direction::Received => Some(hash),
+            TranscriptSecret::Hash(hash) if hash.direction == direction => Some(hash),
             _ => None,
         })
         .collect()
 }
 
+/// Plaintext, blinder and hash of a transcript commitment, checked locally
 #[derive(Debug)]
-pub struct ZKProofInput {
-    recipient: [u8; 20],
-    server_hash: [u8; 32],
-    id_commitment: [u8; 32],
-    status_commitment: Vec<u8>,
-    nuip: String,
-    salt: [u8; 32],
-    server_domain: String,
-    status: Vec<u8>,
-    status_blinder: Vec<u8>,
+struct CommitmentOpening {
+    plaintext: Vec<u8>,
+    blinder: Vec<u8>,
+    hash: Vec<u8>,
 }
 
 // Verify that the blinded, committed hash is correct locally before ZK
-fn prepare_zk_proof_input(
-    received: &[u8],
-    received_commitment: &PlaintextHash,
-    received_secret: &PlaintextHashSecret,
-    recipient: [u8; 20],
-    server_domain: &str,
-    nuip: &str,
-    salt: [u8; 32],
-) -> Result<ZKProofInput, Box<dyn std::error::Error>> {
-    assert_eq!(received_commitment.direction, Direction::Received);
-    assert_eq!(received_commitment.hash.alg, HashAlgId::SHA256);
+fn open_commitment(
+    data: &[u8],
+    commitment: &PlaintextHash,
+    secret: &PlaintextHashSecret,
+) -> Result<CommitmentOpening> {
+    if commitment.direction != secret.direction {
+        return Err(NotaryError::commitment("Commitment and secret directions differ"));
+    }
+    if commitment.hash.alg != HashAlgId::SHA256 {
+        return Err(NotaryError::commitment("Commitment hash algo mismatch"));
+    }
 
-    let hash = &received_commitment.hash;
-
-    let start = received_commitment.idx.min().ok_or("No start index")?;
-    let end = received_commitment.idx.end().ok_or("No end index")?;
-    
-    // This is the raw bytes of the value, e.g. "No Vigente (Fallecido)" (including quotes if string?)
-    // Spansy usually gives the span of the value.
-    let status_bytes = received[start..end].to_vec();
-    
-    // Check if it includes quotes?
-    // If spansy returns the JSON value string including quotes, we might need to strip them 
-    // or the circuit expects them?
-    // The circuit expects `status: str<22>`. Noir strings are bytes. 
-    // If the JSON is `"No Vigente (Fallecido)"`, that's > 22 bytes.
-    // Assuming we need to handle quotes.
-    // For now passing raw bytes.
-    
-    let blinder = received_secret.blinder.as_bytes().to_vec();
-    let committed_hash = hash.value.as_bytes().to_vec();
+    let start = commitment
+        .idx
+        .min()
+        .ok_or_else(|| NotaryError::commitment("No start index"))?;
+    let end = commitment
+        .idx
+        .end()
+        .ok_or_else(|| NotaryError::commitment("No end index"))?;
+
+    // Raw bytes of the JSON value, e.g. "No Vigente (Fallecido)" including quotes
+    // for strings, or the digits of the NUIP
+    let plaintext = data
+        .get(start..end)
+        .ok_or_else(|| NotaryError::commitment("Commitment range out of bounds"))?
+        .to_vec();
+
+    let blinder = secret.blinder.as_bytes().to_vec();
+    let committed_hash = commitment.hash.value.as_bytes().to_vec();
 
     // Verify locally
     let mut hasher = Sha256::new();
-    hasher.update(&status_bytes);
+    hasher.update(&plaintext);
     hasher.update(&blinder);
     let computed_hash = hasher.finalize();
 
     if committed_hash != computed_hash.as_slice() {
-        return Err("Computed hash does not match committed hash".into());
+        return Err(NotaryError::commitment("Computed hash does not match committed hash"));
     }
-    
+
+    Ok(CommitmentOpening {
+        plaintext,
+        blinder,
+        hash: committed_hash,
+    })
+}
+
+#[derive(Debug)]
+pub struct ZKProofInput {
+    recipient: [u8; 20],
+    server_hash: [u8; 32],
+    id_commitment: [u8; 32],
+    status_commitment: Vec<u8>,
+    nuip_commitment: Vec<u8>,
+    nuip: Nuip,
+    salt: Salt,
+    server_domain: RegistryDomain,
+    status: Vec<u8>,
+    status_blinder: Vec<u8>,
+    nuip_blinder: Vec<u8>,
+}
+
+fn prepare_zk_proof_input(
+    status_opening: CommitmentOpening,
+    nuip_opening: CommitmentOpening,
+    recipient: EthAddress,
+    server_domain: RegistryDomain,
+    nuip: &Nuip,
+    salt: Salt,
+) -> Result<ZKProofInput> {
+    // The NUIP sent to the registry must be the one behind id_commitment,
+    // otherwise the circuit's NUIP commitment check fails
+    if nuip_opening.plaintext != nuip.as_str().as_bytes() {
+        return Err(NotaryError::commitment(
+            "NUIP committed in the MPC-TLS request does not match the requested NUIP",
+        ));
+    }
+
     // Derived inputs - MUST match circuit padding
-    let mut server_domain_padded = server_domain.as_bytes().to_vec();
-    server_domain_padded.resize(40, 32); // Pad with spaces
-    let mut hasher = Sha256::new();
-    hasher.update(&server_domain_padded);
-    let server_hash: [u8; 32] = hasher.finalize().into();
-    
-    let mut nuip_padded = nuip.as_bytes().to_vec();
-    nuip_padded.resize(15, 0); // Pad with zeros
-    let mut hasher = Sha256::new();
-    hasher.update(&nuip_padded);
-    hasher.update(&salt);
-    let id_commitment: [u8; 32] = hasher.finalize().into();
+    let server_hash = server_domain.server_hash();
+    let id_commitment = id_commitment(nuip, salt);
 
     Ok(ZKProofInput {
-        recipient,
+        recipient: recipient.into(),
         server_hash,
         id_commitment,
-        status_commitment: committed_hash,
-        nuip: nuip.to_string(),
+        status_commitment: status_opening.hash,
+        nuip_commitment: nuip_opening.hash,
+        nuip: nuip.clone(),
         salt,
-        server_domain: server_domain.to_string(),
-        status: status_bytes,
-        status_blinder: blinder,
+        server_domain,
+        status: status_opening.plaintext,
+        status_blinder: status_opening.blinder,
+        nuip_blinder: nuip_opening.blinder,
     })
 }
 
-fn generate_zk_proof(
+/// Build the witness and prove on `proving`, off the async runtime
+async fn generate_zk_proof(
     proof_input: &ZKProofInput,
-) -> Result<ZKProofBundle, Box<dyn std::error::Error>> {
+    progress: &Progress,
+    proving: &ProvingPool,
+) -> Result<ZKProofBundle> {
     tracing::info!("🔒 Generating ZK proof with Noir...");
 
-    const PROGRAM_JSON: &str = include_str!("../circuits.json");
-
-    // 1. Load bytecode from program.json
-    let json: Value = serde_json::from_str(PROGRAM_JSON)?;
-    let bytecode = json["bytecode"]
-        .as_str()
-        .ok_or("bytecode field not found in program.json")?;
-
     let mut inputs: Vec<String> = vec![];
     
     // Order MUST match main.nr arguments:
@@ -370,23 +534,17 @@ fn generate_zk_proof(
     // status_commitment: pub [u8; 32]
     inputs.extend(proof_input.status_commitment.iter().map(|b| b.to_string()));
     
+    // nuip_commitment: pub [u8; 32]
+    inputs.extend(proof_input.nuip_commitment.iter().map(|b| b.to_string()));
+    
     // nuip: str<15>
-    let mut nuip_padded = proof_input.nuip.as_bytes().to_vec();
-    nuip_padded.resize(15, 0); // Pad with 0s
-    inputs.extend(nuip_padded.iter().map(|b| b.to_string()));
+    inputs.extend(proof_input.nuip.padded().iter().map(|b| b.to_string()));
     
     // salt: [u8; 32]
-    inputs.extend(proof_input.salt.iter().map(|b| b.to_string()));
+    inputs.extend(proof_input.salt.as_bytes().iter().map(|b| b.to_string()));
     
     // server_domain: str<40>
-    let domain_bytes = proof_input.server_domain.as_bytes();
-    inputs.extend(domain_bytes.iter().map(|b| b.to_string()));
-    // If domain is shorter than 40, we might need to pad with 0s or spaces depending on Noir logic. 
-    // The circuit test padded with spaces. 
-    // Ideally we pad here to 40.
-    for _ in domain_bytes.len()..40 {
-        inputs.push("32".to_string()); // 32 is space in ascii. Or 0?
-    }
+    inputs.extend(proof_input.server_domain.padded().iter().map(|b| b.to_string()));
     
     // status: str<22>
     let status_clean = if proof_input.status.first() == Some(&34) {
@@ -400,18 +558,29 @@ fn generate_zk_proof(
     
     // status_blinder: [u8; 16]
     inputs.extend(proof_input.status_blinder.iter().map(|b| b.to_string()));
+    
+    // nuip_blinder: [u8; 16]
+    inputs.extend(proof_input.nuip_blinder.iter().map(|b| b.to_string()));
 
     let input_refs: Vec<&str> = inputs.iter().map(String::as_str).collect();
-    let witness = from_vec_str_to_witness_map(input_refs)?;
-
-    // Setup SRS
-    setup_srs_from_bytecode(bytecode, None, false)?;
-
-    // Verification key
-    let vk = get_ultra_honk_verification_key(bytecode, false)?;
-
-    // Generate proof
-    let proof = prove_ultra_honk_keccak(bytecode, witness.clone(), vk.clone(), false, false)?;
+    let witness = from_vec_str_to_witness_map(input_refs).map_err(NotaryError::zk)?;
+
+    // Barretenberg blocks for the whole proof
+    let stages = progress.clone();
+    let (vk, proof) = proving
+        .run(progress, move || {
+            // Bytecode, SRS and verification key: loaded by the first proof, then reused
+            stages.stage(ProofStage::SrsSetup);
+            let circuit = CircuitContext::global()?;
+
+            // Generate proof
+            stages.stage(ProofStage::Proving);
+            let vk = circuit.vk().to_vec();
+            let proof = prove_ultra_honk_keccak(circuit.bytecode(), witness, vk.clone(), false, false)
+                .map_err(NotaryError::zk)?;
+            Ok((vk, proof))
+        })
+        .await?;
     
     tracing::info!("✅ ZK Proof generated successfully!");
     tracing::info!("   Proof size: {} bytes", proof.len());
@@ -421,7 +590,22 @@ fn generate_zk_proof(
     let status_commitment: [u8; 32] = proof_input.status_commitment
         .as_slice()
         .try_into()
-        .map_err(|_| format!("status_commitment must be exactly 32 bytes, got {}", proof_input.status_commitment.len()))?;
+        .map_err(|_| {
+            NotaryError::commitment(format!(
+                "status_commitment must be exactly 32 bytes, got {}",
+                proof_input.status_commitment.len()
+            ))
+        })?;
+
+    let nuip_commitment: [u8; 32] = proof_input.nuip_commitment
+        .as_slice()
+        .try_into()
+        .map_err(|_| {
+            NotaryError::commitment(format!(
+                "nuip_commitment must be exactly 32 bytes, got {}",
+                proof_input.nuip_commitment.len()
+            ))
+        })?;
 
     // Create PublicInputs struct with ALL the values used in the proof
     let public_inputs = crate::types::PublicInputs {
@@ -429,6 +613,7 @@ fn generate_zk_proof(
         server_hash: proof_input.server_hash,
         id_commitment: proof_input.id_commitment,
         status_commitment,
+        nuip_commitment,
     };
 
     Ok(ZKProofBundle {
@@ -0,0 +1,167 @@
+/// Bounded pool for CPU-heavy Noir work (SRS setup, verification keys, UltraHonk proving)
+///
+/// Barretenberg calls block for seconds to minutes. Running them on a tokio worker stalls every
+/// other task scheduled there, so they go to the blocking thread pool instead, at most `limit`
+/// at a time. Callers beyond the limit wait for a slot.
+use std::sync::{Arc, OnceLock};
+
+use tokio::sync::Semaphore;
+
+use crate::error::{NotaryError, Result};
+use crate::progress::{Progress, ProofStage};
+
+/// Default for `PROVING_CONCURRENCY`
+///
+/// A single UltraHonk proof already uses every core, so running more at once mostly adds memory.
+pub const DEFAULT_PROVING_CONCURRENCY: usize = 1;
+
+/// Runs Noir work off the async runtime, `limit` jobs at a time
+///
+/// Clones share the same slots.
+#[derive(Clone)]
+pub struct ProvingPool {
+    slots: Arc<Semaphore>,
+    limit: usize,
+}
+
+impl ProvingPool {
+    /// Pool running at most `limit` (at least 1) jobs at once
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            slots: Arc::new(Semaphore::new(limit)),
+            limit,
+        }
+    }
+
+    /// Process-wide pool, limited by `PROVING_CONCURRENCY` (default [`DEFAULT_PROVING_CONCURRENCY`])
+    pub fn global() -> &'static ProvingPool {
+        static POOL: OnceLock<ProvingPool> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let limit = std::env::var("PROVING_CONCURRENCY")
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(DEFAULT_PROVING_CONCURRENCY);
+            ProvingPool::new(limit)
+        })
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Jobs running right now
+    pub fn busy(&self) -> usize {
+        self.limit - self.slots.available_permits()
+    }
+
+    /// Run `work` on the blocking pool once a slot is free
+    ///
+    /// Waiting for a slot is reported to `progress` as [`ProofStage::ProverQueue`]. Dropping the
+    /// returned future before `work` started gives the slot back; once started, `work` runs to
+    /// completion and keeps its slot until then.
+    pub async fn run<T, F>(&self, progress: &Progress, work: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        let slot = match self.slots.clone().try_acquire_owned() {
+            Ok(slot) => slot,
+            Err(_) => {
+                progress.stage(ProofStage::ProverQueue);
+                tracing::info!("⏳ All {} proving slots busy, waiting...", self.limit);
+                self.slots
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(NotaryError::internal)?
+            }
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let _slot = slot;
+            work()
+        })
+        .await
+        .map_err(NotaryError::internal)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pool_limits_concurrency() {
+        let pool = ProvingPool::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..6)
+            .map(|i| {
+                let pool = pool.clone();
+                let running = running.clone();
+                let peak = peak.clone();
+                tokio::spawn(async move {
+                    pool.run(&Progress::default(), move || {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(20));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(i)
+                    })
+                    .await
+                })
+            })
+            .collect();
+
+        for (i, task) in tasks.into_iter().enumerate() {
+            assert_eq!(task.await.unwrap().unwrap(), i);
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.busy(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_waiting_is_reported() {
+        let pool = ProvingPool::new(1);
+        let stages = Arc::new(Mutex::new(Vec::new()));
+        let sink = stages.clone();
+        let progress = Progress::new(move |event| sink.lock().unwrap().push(event.stage()));
+
+        // Uncontended: no queue stage
+        pool.run(&progress, || Ok(())).await.unwrap();
+        assert!(stages.lock().unwrap().is_empty());
+
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let holder = pool.clone();
+        let busy = tokio::spawn(async move {
+            holder
+                .run(&Progress::default(), move || {
+                    released.recv().unwrap();
+                    Ok(())
+                })
+                .await
+        });
+        while pool.busy() == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        let waiting = tokio::spawn({
+            let pool = pool.clone();
+            let progress = progress.clone();
+            async move { pool.run(&progress, || Ok(())).await }
+        });
+        while stages.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(*stages.lock().unwrap(), [ProofStage::ProverQueue]);
+
+        release.send(()).unwrap();
+        busy.await.unwrap().unwrap();
+        waiting.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+/// Civil registry adapters - describe how to query a registry and read its answer
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use hyper::Uri;
+use serde_json::Value;
+
+use crate::error::{NotaryError, Result};
+use crate::inputs::{Nuip, RegistryDomain};
+use crate::tls::TlsTrust;
+
+/// Default civil registry endpoint (Colombian registry mock on Railway)
+pub const DEFAULT_REGISTRY_URL: &str =
+    "https://web-production-05160.up.railway.app/VigenciaCedula/consulta";
+
+/// Status returned by the Colombian registry for a deceased person
+/// The circuit (`check_status`) accepts exactly this value
+pub const COLOMBIA_DECEASED_STATUS: &str = "No Vigente (Fallecido)";
+
+/// A civil registry that ProofHeir can prove death records against
+///
+/// Implementations decide how the HTTP request is built and how the JSON
+/// response is interpreted. The prover commits to the value of
+/// [`RegistryAdapter::status_field`], so the deceased value must also be
+/// accepted by the Noir circuit.
+#[async_trait]
+pub trait RegistryAdapter: Send + Sync {
+    /// Full HTTPS URL of the status query endpoint
+    fn url(&self) -> &str;
+
+    /// Host of [`RegistryAdapter::url`] - the `server_domain` the proof commits to
+    fn domain(&self) -> Result<RegistryDomain> {
+        RegistryDomain::from_url(self.url())
+    }
+
+    /// JSON body sent to the registry for the given national ID
+    ///
+    /// Fails with `INVALID_INPUT` for IDs the registry does not accept. Callers go through
+    /// [`RegistryAdapter::request_body`], which also checks the ID is sent unchanged.
+    fn build_request(&self, nuip: &str) -> Result<Value>;
+
+    /// [`build_request`](Self::build_request), checked to carry exactly the bytes of `nuip` in
+    /// [`id_field`](Self::id_field)
+    ///
+    /// The prover commits to that value and the circuit hashes the same bytes into
+    /// `id_commitment`, so an ID the request would rewrite (a JSON number drops leading zeros)
+    /// could only fail with `COMMITMENT_MISMATCH` after the whole MPC-TLS run.
+    fn request_body(&self, nuip: &Nuip) -> Result<Value> {
+        let body = self.build_request(nuip.as_str())?;
+        let sent = match body.get(self.id_field()) {
+            Some(Value::Number(number)) => number.to_string(),
+            Some(Value::String(string)) => string.clone(),
+            _ => {
+                return Err(NotaryError::invalid_input(format!(
+                    "Registry request has no '{}' value",
+                    self.id_field()
+                )))
+            }
+        };
+        if sent != nuip.as_str() {
+            return Err(NotaryError::invalid_input(
+                "NUIP would not reach the registry as entered; use its canonical form (digits, no leading zeros)",
+            ));
+        }
+        Ok(body)
+    }
+
+    /// Check the registry accepts `nuip` as is, before any network I/O
+    fn validate_id(&self, nuip: &Nuip) -> Result<()> {
+        self.request_body(nuip).map(|_| ())
+    }
+
+    /// Top-level JSON field in the request body carrying the national ID
+    /// This value is committed, never revealed, to the verifier
+    fn id_field(&self) -> &str;
+
+    /// Top-level JSON field in the response holding the vital status
+    fn status_field(&self) -> &str;
+
+    /// Whether a status value means the subject is deceased
+    fn is_deceased(&self, status: &str) -> bool;
+
+    /// Plain HTTPS query run before MPC-TLS to fail fast when the subject is alive
+    async fn pre_check(&self, nuip: &Nuip, tls: &TlsTrust) -> Result<()> {
+        let body = self.request_body(nuip)?;
+
+        let res = tls
+            .http_client()?
+            .post(self.url())
+            .json(&body)
+            .send()
+            .await
+            .map_err(NotaryError::registry)?;
+
+        if !res.status().is_success() {
+            return Err(NotaryError::registry(format!(
+                "Failed to query civil registry: {}",
+                res.status()
+            )));
+        }
+
+        let response_data: Value = res
+            .json()
+            .await
+            .map_err(|e| NotaryError::registry(format!("Malformed registry response: {}", e)))?;
+
+        let status = response_data
+            .get(self.status_field())
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                NotaryError::registry(format!(
+                    "Registry response has no '{}' field",
+                    self.status_field()
+                ))
+            })?;
+
+        if !self.is_deceased(status) {
+            tracing::error!("❌ Pre-verification FAILED: Subject is '{}'", status);
+            return Err(NotaryError::SubjectAlive);
+        }
+
+        Ok(())
+    }
+}
+
+/// Colombian civil registry (`VigenciaCedula/consulta`) - the default adapter
+#[derive(Debug, Clone)]
+pub struct ColombiaRegistry {
+    url: String,
+}
+
+impl ColombiaRegistry {
+    /// Client IP reported to the registry in every query
+    const CLIENT_IP: &'static str = "143.137.96.53";
+
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    /// Reads `CIVIL_REGISTRY_URL`, falling back to `CIVIL_REGISTRY_DOMAIN`
+    /// and finally to the Railway deployment
+    pub fn from_env() -> Self {
+        let url = std::env::var("CIVIL_REGISTRY_URL").unwrap_or_else(|_| {
+            std::env::var("CIVIL_REGISTRY_DOMAIN")
+                .map(|domain| format!("https://{}/VigenciaCedula/consulta", domain))
+                .unwrap_or_else(|_| DEFAULT_REGISTRY_URL.to_string())
+        });
+        Self::new(url)
+    }
+}
+
+impl Default for ColombiaRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_REGISTRY_URL)
+    }
+}
+
+#[async_trait]
+impl RegistryAdapter for ColombiaRegistry {
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn build_request(&self, nuip: &str) -> Result<Value> {
+        // Sent as a JSON number, so only digits without leading zeros reach the registry unchanged
+        if !nuip.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(NotaryError::invalid_input(
+                "The Colombian registry only accepts NUIPs made of digits",
+            ));
+        }
+        if nuip.starts_with('0') {
+            return Err(NotaryError::invalid_input(
+                "NUIP must not start with 0: the Colombian registry reads it as a number",
+            ));
+        }
+        let nuip_number: u64 = nuip
+            .parse()
+            .map_err(|_| NotaryError::invalid_input("Invalid NUIP format"))?;
+
+        Ok(serde_json::json!({
+            "nuip": nuip_number,
+            "ip": Self::CLIENT_IP
+        }))
+    }
+
+    fn id_field(&self) -> &str {
+        "nuip"
+    }
+
+    fn status_field(&self) -> &str {
+        "vigencia"
+    }
+
+    fn is_deceased(&self, status: &str) -> bool {
+        status == COLOMBIA_DECEASED_STATUS
+    }
+}
+
+/// Resolve the socket address of the registry's HTTPS endpoint
+pub async fn resolve_registry_addr(registry: &dyn RegistryAdapter) -> Result<SocketAddr> {
+    let uri = registry
+        .url()
+        .parse::<Uri>()
+        .map_err(NotaryError::invalid_input)?;
+    let host = uri
+        .host()
+        .ok_or_else(|| NotaryError::invalid_input("URI must have authority"))?;
+    let port = uri.port_u16().unwrap_or(443);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(NotaryError::registry)?
+        .collect();
+
+    // Prefer IPv4: `localhost` often resolves to `::1` first while local registries bind 127.0.0.1
+    addrs
+        .iter()
+        .find(|addr| addr.is_ipv4())
+        .or_else(|| addrs.first())
+        .copied()
+        .ok_or_else(|| NotaryError::registry("Failed to lookup host"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nuip(nuip: &str) -> Nuip {
+        Nuip::new(nuip).unwrap()
+    }
+
+    #[test]
+    fn test_colombia_request_carries_nuip_unchanged() {
+        let registry = ColombiaRegistry::default();
+
+        let body = registry.request_body(&nuip("454545454")).unwrap();
+        assert_eq!(body["nuip"].to_string(), "454545454");
+
+        // Letters and leading zeros would not reach the registry as committed
+        for id in ["12A45", "0123"] {
+            let error = registry.validate_id(&nuip(id)).unwrap_err();
+            assert_eq!(error.code(), "INVALID_INPUT", "{}", id);
+        }
+    }
+
+    #[test]
+    fn test_rewritten_id_is_rejected() {
+        struct Rewriting;
+
+        #[async_trait]
+        impl RegistryAdapter for Rewriting {
+            fn url(&self) -> &str {
+                DEFAULT_REGISTRY_URL
+            }
+
+            fn build_request(&self, nuip: &str) -> Result<Value> {
+                Ok(serde_json::json!({ "id": nuip.to_ascii_uppercase() }))
+            }
+
+            fn id_field(&self) -> &str {
+                "id"
+            }
+
+            fn status_field(&self) -> &str {
+                "status"
+            }
+
+            fn is_deceased(&self, _status: &str) -> bool {
+                true
+            }
+        }
+
+        assert!(Rewriting.validate_id(&nuip("AB12")).is_ok());
+        let error = Rewriting.validate_id(&nuip("ab12")).unwrap_err();
+        assert_eq!(error.code(), "INVALID_INPUT");
+    }
+}
@@ -0,0 +1,650 @@
+/// Remote notary - runs the verifier in a separate process reachable over TCP
+///
+/// One TCP connection carries three logical channels, each framed as
+/// `[channel: u8][len: u32 BE][payload]`. A zero-length frame closes the
+/// sender's write side of that channel.
+///
+/// * `MPC_CHANNEL` - the MPC-TLS session (the verifier's main socket)
+/// * `BUNDLE_CHANNEL` - the bincode `ZKProofBundle` (the verifier's extra socket)
+/// * `CONTROL_CHANNEL` - a `SessionRequest` from the prover, then a `SessionOutcome` from the notary
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::io::{
+    AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::chain::ProofHeirError;
+use crate::error::{NotaryError, Result};
+use crate::inputs::EthAddress;
+use crate::proof_gen::AbortOnDrop;
+use crate::prover::{prover, ProverParams};
+use crate::submitter::{Submission, SubmissionMode};
+use crate::types::{PublicInputMismatch, ZKProofBundle};
+use crate::verifier::{verifier, VerifierOptions};
+
+/// Default address the notary server listens on
+pub const DEFAULT_NOTARY_ADDR: &str = "127.0.0.1:7047";
+
+pub const MPC_CHANNEL: u8 = 0;
+pub const BUNDLE_CHANNEL: u8 = 1;
+pub const CONTROL_CHANNEL: u8 = 2;
+const CHANNELS: usize = 3;
+
+/// Largest payload carried by a single frame
+const MAX_FRAME_LEN: usize = 1 << 16;
+/// Per-channel buffer, same size as the in-process duplex channels
+const CHANNEL_BUFFER: usize = 1 << 23;
+/// Largest `SessionRequest` or `SessionOutcome` (calldata included) accepted from the peer
+const MAX_CONTROL_MESSAGE_LEN: usize = 1 << 20;
+
+/// Default for `NOTARY_MAX_SESSIONS`
+pub const DEFAULT_MAX_SESSIONS: usize = 4;
+/// Default for `NOTARY_HANDSHAKE_TIMEOUT_SECS`
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default for `NOTARY_SESSION_TIMEOUT_SECS`; MPC-TLS plus proving takes minutes in release builds
+pub const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+
+/// What the notary server lets remote provers do
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    /// Submission modes a prover may request; anything else is rejected with `INVALID_INPUT`
+    ///
+    /// Only `verify_only` by default, so an anonymous prover cannot make the notary spend gas
+    /// (`submit`) or RPC calls (`dry_run`).
+    pub allowed_submission: Vec<SubmissionMode>,
+    /// Sessions served at once; further connections wait to be accepted
+    pub max_sessions: usize,
+    /// How long a connected prover has to send its `SessionRequest`
+    pub handshake_timeout: Duration,
+    /// How long a whole session may take before the connection is dropped
+    pub session_timeout: Duration,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            allowed_submission: vec![SubmissionMode::VerifyOnly],
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Allowed modes from `NOTARY_ALLOWED_SUBMISSION` (comma-separated, e.g. `verify_only,submit`),
+    /// session limit from `NOTARY_MAX_SESSIONS`, timeouts from `NOTARY_HANDSHAKE_TIMEOUT_SECS` and
+    /// `NOTARY_SESSION_TIMEOUT_SECS`
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+
+        let allowed_submission = match std::env::var("NOTARY_ALLOWED_SUBMISSION") {
+            Ok(modes) => modes
+                .split(',')
+                .filter(|mode| !mode.trim().is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<_>>>()?,
+            Err(_) => defaults.allowed_submission,
+        };
+
+        let max_sessions = std::env::var("NOTARY_MAX_SESSIONS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(defaults.max_sessions);
+
+        let seconds = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+        };
+
+        Ok(Self {
+            allowed_submission,
+            max_sessions: max_sessions.max(1),
+            handshake_timeout: seconds("NOTARY_HANDSHAKE_TIMEOUT_SECS")
+                .unwrap_or(defaults.handshake_timeout),
+            session_timeout: seconds("NOTARY_SESSION_TIMEOUT_SECS")
+                .unwrap_or(defaults.session_timeout),
+        })
+    }
+
+    fn check_submission(&self, mode: SubmissionMode) -> Result<()> {
+        if self.allowed_submission.contains(&mode) {
+            Ok(())
+        } else {
+            Err(NotaryError::invalid_input(format!(
+                "Submission mode {:?} is not allowed by this notary (allowed: {:?})",
+                mode, self.allowed_submission
+            )))
+        }
+    }
+}
+
+/// Sent by the prover when it opens a session
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionRequest {
+    /// Delegated account the notary submits the proof to
+    pub testator_address: [u8; 20],
+    /// What the notary does with the claim once verified
+    pub submission: SubmissionMode,
+}
+
+/// Sent by the notary once the verifier finished
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SessionOutcome {
+    Verified(Submission),
+    Rejected {
+        code: String,
+        message: String,
+        /// Decoded contract error for `CONTRACT_REVERTED` and `PREFLIGHT_FAILED`
+        contract_error: Option<ProofHeirError>,
+        /// Offending fields for `PUBLIC_INPUTS_MISMATCH`
+        mismatches: Vec<PublicInputMismatch>,
+    },
+}
+
+impl SessionOutcome {
+    fn from_result(result: &Result<Submission>) -> Self {
+        match result {
+            Ok(submission) => SessionOutcome::Verified(submission.clone()),
+            Err(e) => SessionOutcome::Rejected {
+                code: e.code().to_string(),
+                message: e.to_string(),
+                contract_error: match e {
+                    NotaryError::ContractReverted(error) | NotaryError::Preflight(error) => {
+                        Some(error.clone())
+                    }
+                    _ => None,
+                },
+                mismatches: match e {
+                    NotaryError::PublicInputsMismatch(mismatches) => mismatches.clone(),
+                    _ => Vec::new(),
+                },
+            },
+        }
+    }
+
+    /// Rebuild the notary's error on the prover side, keeping its variant (and code)
+    fn into_result(self) -> Result<Submission> {
+        let (code, message, contract_error, mismatches) = match self {
+            SessionOutcome::Verified(submission) => return Ok(submission),
+            SessionOutcome::Rejected {
+                code,
+                message,
+                contract_error,
+                mismatches,
+            } => (code, message, contract_error, mismatches),
+        };
+
+        Err(match (code.as_str(), contract_error) {
+            ("CONTRACT_REVERTED", Some(error)) => NotaryError::ContractReverted(error),
+            ("PREFLIGHT_FAILED", Some(error)) => NotaryError::Preflight(error),
+            ("PUBLIC_INPUTS_MISMATCH", _) if !mismatches.is_empty() => {
+                NotaryError::PublicInputsMismatch(mismatches)
+            }
+            _ => NotaryError::from_remote(&code, message),
+        })
+    }
+}
+
+/// Logical channels of a multiplexed connection
+pub struct Channels {
+    pub mpc: DuplexStream,
+    pub bundle: DuplexStream,
+    pub control: DuplexStream,
+}
+
+/// Multiplex `io` into [`Channels`]
+///
+/// The returned task drives the connection; it finishes once every local channel
+/// is closed and the peer closed its side.
+pub fn multiplex<T>(io: T) -> (Channels, JoinHandle<io::Result<()>>)
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let (io_read, io_write) = tokio::io::split(io);
+
+    let mut locals = Vec::with_capacity(CHANNELS);
+    let mut readers = Vec::with_capacity(CHANNELS);
+    let mut writers = Vec::with_capacity(CHANNELS);
+    for _ in 0..CHANNELS {
+        let (local, inner) = tokio::io::duplex(CHANNEL_BUFFER);
+        let (reader, writer) = tokio::io::split(inner);
+        locals.push(local);
+        readers.push(reader);
+        writers.push(Some(writer));
+    }
+
+    let driver = tokio::spawn(async move {
+        let io_write = Arc::new(Mutex::new(io_write));
+
+        let mut outbound = JoinSet::new();
+        for (channel, reader) in readers.into_iter().enumerate() {
+            outbound.spawn(forward_outbound(channel as u8, reader, io_write.clone()));
+        }
+
+        let outbound = async {
+            while let Some(result) = outbound.join_next().await {
+                result.map_err(io::Error::other)??;
+            }
+            io_write.lock().await.shutdown().await
+        };
+
+        let (inbound, outbound) = tokio::join!(forward_inbound(io_read, writers), outbound);
+        inbound.and(outbound)
+    });
+
+    let mut locals = locals.into_iter();
+    let channels = Channels {
+        mpc: locals.next().expect("mpc channel"),
+        bundle: locals.next().expect("bundle channel"),
+        control: locals.next().expect("control channel"),
+    };
+
+    (channels, driver)
+}
+
+/// Copy one local channel into frames on the connection
+async fn forward_outbound<W>(
+    channel: u8,
+    mut reader: ReadHalf<DuplexStream>,
+    io_write: Arc<Mutex<W>>,
+) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0u8; MAX_FRAME_LEN];
+    loop {
+        let n = reader.read(&mut buf).await?;
+
+        let mut io_write = io_write.lock().await;
+        io_write.write_u8(channel).await?;
+        io_write.write_u32(n as u32).await?;
+        io_write.write_all(&buf[..n]).await?;
+        io_write.flush().await?;
+
+        // Zero-length frame closes the channel
+        if n == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Dispatch frames from the connection to the local channels
+async fn forward_inbound<R>(
+    mut io_read: R,
+    mut writers: Vec<Option<WriteHalf<DuplexStream>>>,
+) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut payload = vec![0u8; MAX_FRAME_LEN];
+    loop {
+        let channel = match io_read.read_u8().await {
+            Ok(channel) => channel as usize,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        };
+        let len = io_read.read_u32().await? as usize;
+
+        if channel >= CHANNELS || len > MAX_FRAME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid frame: channel {}, {} bytes", channel, len),
+            ));
+        }
+        io_read.read_exact(&mut payload[..len]).await?;
+
+        // Frames for a channel the local side already dropped are discarded
+        let Some(writer) = writers[channel].as_mut() else {
+            continue;
+        };
+
+        let written = if len == 0 {
+            writer.shutdown().await
+        } else {
+            writer.write_all(&payload[..len]).await
+        };
+
+        if len == 0 || written.is_err() {
+            writers[channel] = None;
+        }
+    }
+
+    // Peer is gone: close every channel still open
+    for writer in writers.iter_mut().flatten() {
+        let _ = writer.shutdown().await;
+    }
+    Ok(())
+}
+
+/// Send one bincode message and close the write side of the channel
+async fn send_message<M: Serialize>(channel: &mut DuplexStream, message: &M) -> Result<()> {
+    let bytes = bincode::serialize(message).map_err(NotaryError::internal)?;
+    channel.write_all(&bytes).await.map_err(NotaryError::mpc_tls)?;
+    channel.shutdown().await.map_err(NotaryError::mpc_tls)
+}
+
+/// Receive one bincode message (everything until the peer closes its side, at most
+/// `MAX_CONTROL_MESSAGE_LEN` bytes)
+async fn receive_message<M: DeserializeOwned>(channel: &mut DuplexStream) -> Result<M> {
+    let mut buf = Vec::new();
+    channel
+        .take(MAX_CONTROL_MESSAGE_LEN as u64 + 1)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(NotaryError::mpc_tls)?;
+    if buf.len() > MAX_CONTROL_MESSAGE_LEN {
+        return Err(NotaryError::mpc_tls(format!(
+            "Control message exceeds {} bytes",
+            MAX_CONTROL_MESSAGE_LEN
+        )));
+    }
+    bincode::deserialize(&buf)
+        .map_err(|e| NotaryError::mpc_tls(format!("Malformed control message: {}", e)))
+}
+
+/// Run the prover against a notary server at `notary_addr`
+///
+/// Returns the proof bundle, and what the notary did with the claim, once it reports the
+/// session as verified. The notary's verification and submission are reported to
+/// `params.progress` as a single verification stage.
+pub async fn prove_with_remote_notary(
+    notary_addr: &str,
+    params: &ProverParams<'_>,
+    testator_address: EthAddress,
+    submission: SubmissionMode,
+) -> Result<(ZKProofBundle, Submission)> {
+    let tcp = TcpStream::connect(notary_addr)
+        .await
+        .map_err(|e| NotaryError::mpc_tls(format!("Failed to connect to notary {}: {}", notary_addr, e)))?;
+    let (channels, driver) = multiplex(tcp);
+    let driver = AbortOnDrop(driver);
+    let Channels {
+        mpc,
+        bundle,
+        mut control,
+    } = channels;
+
+    send_message(
+        &mut control,
+        &SessionRequest {
+            testator_address: testator_address.into(),
+            submission,
+        },
+    )
+    .await?;
+
+    let proof_bundle = prover(mpc, bundle, params).await;
+    let outcome = receive_message::<SessionOutcome>(&mut control).await;
+    drop(control);
+
+    if let Err(e) = driver.await.map_err(NotaryError::internal)? {
+        tracing::warn!("Notary connection closed with error: {}", e);
+    }
+
+    match (proof_bundle, outcome.and_then(SessionOutcome::into_result)) {
+        (Ok(proof_bundle), Ok(submission)) => Ok((proof_bundle, submission)),
+        // A notary that refused the session (e.g. a disallowed submission mode) closes the MPC
+        // channel; its verdict says why, the prover's MPC-TLS error does not
+        (Err(NotaryError::MpcTls(_)), Err(e)) if !matches!(e, NotaryError::MpcTls(_)) => Err(e),
+        (Err(e), _) | (Ok(_), Err(e)) => Err(e),
+    }
+}
+
+/// Serve a single prover connection
+///
+/// A session asking for a submission mode `server` does not allow is rejected before MPC-TLS,
+/// and a prover that sends no request within [`ServerOptions::handshake_timeout`] is dropped.
+/// Dropping the returned future (e.g. on [`ServerOptions::session_timeout`]) closes the connection.
+pub async fn serve_connection<T>(io: T, options: &VerifierOptions, server: &ServerOptions) -> Result<()>
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let (channels, driver) = multiplex(io);
+    let driver = AbortOnDrop(driver);
+    let Channels {
+        mpc,
+        bundle,
+        mut control,
+    } = channels;
+
+    let request: SessionRequest = tokio::time::timeout(server.handshake_timeout, receive_message(&mut control))
+        .await
+        .map_err(|_| {
+            NotaryError::mpc_tls(format!(
+                "No session request within {}s",
+                server.handshake_timeout.as_secs()
+            ))
+        })??;
+    tracing::info!(
+        "Session for testator 0x{}",
+        hex::encode(request.testator_address)
+    );
+
+    let result = match server.check_submission(request.submission) {
+        Ok(()) => {
+            let options = VerifierOptions {
+                submission: request.submission,
+                ..options.clone()
+            };
+            verifier(mpc, bundle, request.testator_address, &options)
+                .await
+                .map(|session| session.submission)
+        }
+        Err(e) => {
+            // Closing the MPC and bundle channels ends the prover's side of the session
+            drop((mpc, bundle));
+            Err(e)
+        }
+    };
+    send_message(&mut control, &SessionOutcome::from_result(&result)).await?;
+    drop(control);
+
+    driver
+        .await
+        .map_err(NotaryError::internal)?
+        .map_err(NotaryError::mpc_tls)?;
+
+    result.map(|_| ())
+}
+
+/// Accept prover connections forever, one verifier session per connection
+///
+/// At most [`ServerOptions::max_sessions`] sessions run at once; the next connection is only
+/// accepted once one of them finished. Each session is cut off after
+/// [`ServerOptions::session_timeout`], so idle or stalled provers cannot hold a slot forever.
+pub async fn serve(listener: TcpListener, options: VerifierOptions, server: ServerOptions) -> Result<()> {
+    let sessions = Arc::new(Semaphore::new(server.max_sessions.max(1)));
+    let server = Arc::new(server);
+
+    loop {
+        let slot = sessions
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(NotaryError::internal)?;
+        let (socket, peer) = listener.accept().await.map_err(NotaryError::internal)?;
+        let options = options.clone();
+        let server = server.clone();
+
+        tokio::spawn(async move {
+            let _slot = slot;
+            tracing::info!("Prover connected from {}", peer);
+            let session = serve_connection(socket, &options, &server);
+            match tokio::time::timeout(server.session_timeout, session).await {
+                Ok(Ok(())) => tracing::info!("✅ Session with {} verified", peer),
+                Ok(Err(e)) => tracing::error!("❌ Session with {} failed [{}]: {}", peer, e.code(), e),
+                Err(_) => tracing::error!(
+                    "❌ Session with {} timed out after {}s",
+                    peer,
+                    server.session_timeout.as_secs()
+                ),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_channels_are_independent() {
+        let (a, b) = tokio::io::duplex(1 << 16);
+        let (mut left, left_driver) = multiplex(a);
+        let (mut right, right_driver) = multiplex(b);
+
+        // More than one frame on the MPC channel, interleaved with the control channel
+        let big = vec![0x5A; MAX_FRAME_LEN * 2 + 17];
+        left.mpc.write_all(&big).await.unwrap();
+        left.control.write_all(b"hello").await.unwrap();
+        left.mpc.shutdown().await.unwrap();
+        left.control.shutdown().await.unwrap();
+        left.bundle.shutdown().await.unwrap();
+
+        let mut received = Vec::new();
+        right.mpc.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, big);
+
+        let mut control = Vec::new();
+        right.control.read_to_end(&mut control).await.unwrap();
+        assert_eq!(control, b"hello");
+
+        let mut bundle = Vec::new();
+        right.bundle.read_to_end(&mut bundle).await.unwrap();
+        assert!(bundle.is_empty());
+
+        // Reply on the other direction of the same channel
+        right.control.write_all(b"done").await.unwrap();
+        drop(right);
+
+        let mut reply = Vec::new();
+        left.control.read_to_end(&mut reply).await.unwrap();
+        assert_eq!(reply, b"done");
+        drop(left);
+
+        left_driver.await.unwrap().unwrap();
+        right_driver.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_messages_roundtrip() {
+        let (a, b) = tokio::io::duplex(1 << 16);
+        let (mut prover_side, _) = multiplex(a);
+        let (mut notary_side, _) = multiplex(b);
+
+        let request = SessionRequest {
+            testator_address: [7u8; 20],
+            submission: SubmissionMode::Calldata,
+        };
+        send_message(&mut prover_side.control, &request).await.unwrap();
+        let request: SessionRequest = receive_message(&mut notary_side.control).await.unwrap();
+        assert_eq!(request.testator_address, [7u8; 20]);
+        assert_eq!(request.submission, SubmissionMode::Calldata);
+
+        let rejected: Result<Submission> = Err(NotaryError::SubjectAlive);
+        send_message(&mut notary_side.control, &SessionOutcome::from_result(&rejected))
+            .await
+            .unwrap();
+        let outcome: SessionOutcome = receive_message(&mut prover_side.control).await.unwrap();
+        assert!(matches!(outcome.into_result(), Err(NotaryError::SubjectAlive)));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_control_message_is_rejected() {
+        let (mut peer, mut control) = tokio::io::duplex(1 << 16);
+        let flood = tokio::spawn(async move {
+            let _ = peer.write_all(&vec![0u8; MAX_CONTROL_MESSAGE_LEN + 1]).await;
+            peer
+        });
+
+        let error = receive_message::<SessionRequest>(&mut control).await.unwrap_err();
+        assert!(error.to_string().contains("exceeds"));
+        flood.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_silent_prover_is_dropped_after_handshake_timeout() {
+        let (_prover_io, notary_io) = tokio::io::duplex(1 << 16);
+        let server = ServerOptions {
+            handshake_timeout: Duration::from_millis(50),
+            ..ServerOptions::default()
+        };
+
+        let error = serve_connection(notary_io, &VerifierOptions::from_env(), &server)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), "MPC_TLS_FAILED");
+        assert!(error.to_string().contains("No session request"));
+    }
+
+    #[test]
+    fn test_server_allows_only_verify_only_by_default() {
+        let server = ServerOptions::default();
+        assert!(server.check_submission(SubmissionMode::VerifyOnly).is_ok());
+        for mode in [SubmissionMode::Submit, SubmissionMode::Calldata, SubmissionMode::DryRun] {
+            assert!(matches!(
+                server.check_submission(mode),
+                Err(NotaryError::InvalidInput(_))
+            ));
+        }
+    }
+
+    /// Send `error` through a serialized `SessionOutcome` and rebuild it
+    fn roundtrip(error: NotaryError) -> NotaryError {
+        let bytes = bincode::serialize(&SessionOutcome::from_result(&Err(error))).unwrap();
+        let outcome: SessionOutcome = bincode::deserialize(&bytes).unwrap();
+        outcome.into_result().unwrap_err()
+    }
+
+    #[test]
+    fn test_contract_errors_stay_typed() {
+        assert!(matches!(
+            roundtrip(NotaryError::ContractReverted(ProofHeirError::IdentityNotRegistered)),
+            NotaryError::ContractReverted(ProofHeirError::IdentityNotRegistered)
+        ));
+        assert!(matches!(
+            roundtrip(NotaryError::Preflight(ProofHeirError::IdentityNotRegistered)),
+            NotaryError::Preflight(ProofHeirError::IdentityNotRegistered)
+        ));
+    }
+
+    #[test]
+    fn test_error_codes_survive_the_roundtrip() {
+        let mismatch = PublicInputMismatch {
+            field: "recipient".to_string(),
+            bundle: "11".repeat(20),
+            proof: "22".repeat(20),
+        };
+        let errors = [
+            NotaryError::InvalidInput("nuip".into()),
+            NotaryError::SubjectAlive,
+            NotaryError::UntrustedRegistry("example.com".into()),
+            NotaryError::CommitmentMismatch("status".into()),
+            NotaryError::PublicInputsMismatch(vec![mismatch.clone()]),
+            NotaryError::InvalidProof("bad proof".into()),
+            NotaryError::Preflight(ProofHeirError::IdentityNotRegistered),
+            NotaryError::OnChain("nonce too low".into()),
+            NotaryError::ContractReverted(ProofHeirError::IdentityNotRegistered),
+        ];
+
+        for error in errors {
+            let code = error.code();
+            assert_eq!(roundtrip(error).code(), code);
+        }
+
+        assert!(matches!(
+            roundtrip(NotaryError::PublicInputsMismatch(vec![mismatch.clone()])),
+            NotaryError::PublicInputsMismatch(mismatches) if mismatches == vec![mismatch]
+        ));
+    }
+}
@@ -0,0 +1,388 @@
+/// Submission of verified claims - what happens once the verifier has accepted a proof
+use std::str::FromStr;
+use std::sync::Arc;
+
+use alloy::primitives::{Address, Bytes, B256};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::chain::{
+    AlloyChain, ChainClient, ProofHeirError, SimulationOutcome, TxOutcome, TxReceipt,
+};
+use crate::error::{NotaryError, Result};
+use crate::verifier::VerifiedClaim;
+
+/// What to do with a claim once it is verified
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionMode {
+    /// Verify only; nothing is sent
+    VerifyOnly,
+    /// The notary sends `proveDeathAndRegisterHeir` itself
+    #[default]
+    Submit,
+    /// Return the ABI-encoded call for the heir's wallet to send
+    Calldata,
+    /// Simulate the transaction with `eth_call` but do not send it
+    DryRun,
+}
+
+impl FromStr for SubmissionMode {
+    type Err = NotaryError;
+
+    /// Parse the snake_case name used in JSON (`verify_only`, `submit`, `calldata`, `dry_run`)
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "verify_only" => Ok(SubmissionMode::VerifyOnly),
+            "submit" => Ok(SubmissionMode::Submit),
+            "calldata" => Ok(SubmissionMode::Calldata),
+            "dry_run" => Ok(SubmissionMode::DryRun),
+            other => Err(NotaryError::invalid_input(format!(
+                "Unknown submission mode '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+impl SubmissionMode {
+    /// Mode used when the caller does not pick one: `SUBMISSION_MODE` if set, otherwise
+    /// `verify_only` with a remote notary (the only mode `notary_server` allows by default) and
+    /// `submit` with the verifier in-process
+    pub fn from_env(remote_notary: bool) -> Self {
+        let fallback = if remote_notary {
+            SubmissionMode::VerifyOnly
+        } else {
+            SubmissionMode::Submit
+        };
+
+        match std::env::var("SUBMISSION_MODE") {
+            Ok(mode) if !mode.trim().is_empty() => mode.parse().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring SUBMISSION_MODE: {}", e);
+                fallback
+            }),
+            _ => fallback,
+        }
+    }
+}
+
+/// Result of applying a [`SubmissionMode`] to a verified claim
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    /// Verified, not submitted
+    Skipped,
+    /// Sent by the notary and confirmed
+    Submitted(TxReceipt),
+    /// `proveDeathAndRegisterHeir` calldata to send to the testator's delegated account
+    Calldata { to: [u8; 20], data: Vec<u8> },
+    /// The transaction would succeed; nothing was sent
+    Simulated,
+}
+
+/// Apply `mode` to `claim`, using `submitter` in [`SubmissionMode::Submit`] and [`SubmissionMode::DryRun`]
+pub async fn settle_claim(
+    claim: &VerifiedClaim,
+    mode: SubmissionMode,
+    submitter: &dyn ClaimSubmitter,
+) -> Result<Submission> {
+    match mode {
+        SubmissionMode::VerifyOnly => {
+            tracing::info!("Submission skipped (verify_only)");
+            Ok(Submission::Skipped)
+        }
+        SubmissionMode::Submit => Ok(Submission::Submitted(submitter.submit(claim).await?)),
+        SubmissionMode::Calldata => Ok(Submission::Calldata {
+            to: claim.testator_address,
+            data: claim.calldata()?,
+        }),
+        SubmissionMode::DryRun => {
+            submitter.simulate(claim).await?;
+            Ok(Submission::Simulated)
+        }
+    }
+}
+
+/// Delivers a [`VerifiedClaim`] to the testator's delegated account
+///
+/// The default is [`ChainSubmitter`]; relayers or wallet hand-offs implement this trait instead.
+#[async_trait]
+pub trait ClaimSubmitter: Send + Sync {
+    /// Submit the claim; anything but a confirmed transaction is an error
+    async fn submit(&self, claim: &VerifiedClaim) -> Result<TxReceipt>;
+
+    /// Check that submitting the claim would succeed, without sending anything
+    async fn simulate(&self, claim: &VerifiedClaim) -> Result<()>;
+}
+
+/// Sends `proveDeathAndRegisterHeir` directly through a [`ChainClient`]
+#[derive(Clone)]
+pub struct ChainSubmitter {
+    chain: Arc<dyn ChainClient>,
+}
+
+impl ChainSubmitter {
+    pub fn new(chain: Arc<dyn ChainClient>) -> Self {
+        Self { chain }
+    }
+
+    /// Submitter signing with `VERIFIER_PRIVATE_KEY` against `RPC_URL` (see [`AlloyChain::from_env`])
+    pub fn from_env() -> Self {
+        Self::new(Arc::new(AlloyChain::from_env()))
+    }
+}
+
+#[async_trait]
+impl ClaimSubmitter for ChainSubmitter {
+    async fn submit(&self, claim: &VerifiedClaim) -> Result<TxReceipt> {
+        // Abort on a revert before paying gas for it
+        self.simulate(claim).await?;
+
+        tracing::info!("🔐 Sending transaction: proveDeathAndRegisterHeir()...");
+        let (account, proof, public_inputs) = call_args(claim)?;
+        match self
+            .chain
+            .prove_death_and_register_heir(account, proof, public_inputs)
+            .await?
+        {
+            TxOutcome::Confirmed(receipt) => {
+                tracing::info!(
+                    "✅ Transaction confirmed in block: {}",
+                    receipt.block_number
+                );
+                tracing::info!("✅ On-chain proof verification succeeded!");
+                tracing::info!("✅ Heir registered in testator's delegated account storage!");
+                tracing::info!("⛽ Gas used: {}", receipt.gas_used);
+                Ok(receipt)
+            }
+            TxOutcome::Reverted { tx_hash, data } => {
+                tracing::error!("❌ Transaction reverted!");
+                Err(revert_error(
+                    "Transaction was reverted by the contract",
+                    tx_hash,
+                    data,
+                ))
+            }
+            TxOutcome::Dropped { tx_hash, reason } => {
+                tracing::error!("❌ Transaction {} was not confirmed: {}", tx_hash, reason);
+                Err(NotaryError::on_chain(format!(
+                    "Transaction {} was not confirmed: {}",
+                    tx_hash, reason
+                )))
+            }
+        }
+    }
+
+    async fn simulate(&self, claim: &VerifiedClaim) -> Result<()> {
+        tracing::info!("🧪 Simulating proveDeathAndRegisterHeir() with eth_call...");
+        let (account, proof, public_inputs) = call_args(claim)?;
+        match self
+            .chain
+            .simulate_prove_death_and_register_heir(account, proof, public_inputs)
+            .await?
+        {
+            SimulationOutcome::Success => {
+                tracing::info!("✅ Simulation succeeded");
+                Ok(())
+            }
+            SimulationOutcome::Reverted { data } => {
+                tracing::error!("❌ Simulation reverted!");
+                Err(revert_error("Simulation reverted", None, data))
+            }
+        }
+    }
+}
+
+/// `proveDeathAndRegisterHeir` arguments for `claim`, sent to the testator's delegated account
+fn call_args(claim: &VerifiedClaim) -> Result<(Address, Bytes, Vec<B256>)> {
+    // Call the function on the testator's delegated account, not on the ProofHeir contract
+    tracing::info!(
+        "📡 proveDeathAndRegisterHeir on delegated account: 0x{}",
+        hex::encode(claim.testator_address)
+    );
+
+    // Send only the proof body: the Solidity verifier takes public inputs separately
+    Ok((
+        Address::from(claim.testator_address),
+        Bytes::copy_from_slice(&claim.proof_body),
+        claim.solidity_public_inputs()?,
+    ))
+}
+
+/// Typed error for ProofHeir custom errors, `ONCHAIN_FAILED` with the raw data otherwise
+fn revert_error(context: &str, tx_hash: Option<B256>, data: Option<Bytes>) -> NotaryError {
+    if let Some(error) = data.as_deref().and_then(ProofHeirError::decode) {
+        tracing::error!("❌ ProofHeir error: {} ({})", error.name(), error);
+        return NotaryError::ContractReverted(error);
+    }
+
+    let tx = tx_hash
+        .map(|hash| format!(" (tx {})", hash))
+        .unwrap_or_default();
+    let data = data
+        .map(|data| format!(", revert data 0x{}", hex::encode(data)))
+        .unwrap_or_default();
+    NotaryError::on_chain(format!("{}{}{}", context, tx, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::{MockChain, MockOutcome, ProofHeir};
+    use crate::types::{PublicInputs, PUBLIC_INPUT_FIELDS};
+    use alloy::sol_types::{SolCall, SolError};
+    use tlsn::transcript::PartialTranscript;
+
+    const TESTATOR: [u8; 20] = [0xcd; 20];
+
+    fn claim() -> VerifiedClaim {
+        VerifiedClaim {
+            testator_address: TESTATOR,
+            server_name: "registry.example.com".to_string(),
+            public_inputs: PublicInputs {
+                recipient: [0xab; 20],
+                server_hash: [1; 32],
+                id_commitment: [2; 32],
+                status_commitment: [3; 32],
+                nuip_commitment: [4; 32],
+            },
+            proof_body: vec![0x42; 64],
+            transcript: PartialTranscript::new(0, 0),
+        }
+    }
+
+    #[test]
+    fn test_submission_mode_from_str() {
+        assert_eq!("verify_only".parse::<SubmissionMode>().unwrap(), SubmissionMode::VerifyOnly);
+        assert_eq!(" dry_run ".parse::<SubmissionMode>().unwrap(), SubmissionMode::DryRun);
+        assert!("DryRun".parse::<SubmissionMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_submit_confirmed() {
+        let chain = Arc::new(MockChain::default());
+        let receipt = ChainSubmitter::new(chain.clone())
+            .submit(&claim())
+            .await
+            .unwrap();
+        assert_eq!(receipt.block_number, MockChain::BLOCK_NUMBER);
+        assert_eq!(receipt.gas_used, MockChain::GAS_USED);
+
+        // One call, to the delegated account, with the proof body and one field per public input byte
+        let calls = chain.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].account, Address::from(TESTATOR));
+        assert_eq!(calls[0].proof.as_ref(), &[0x42; 64]);
+        assert_eq!(calls[0].public_inputs.len(), PUBLIC_INPUT_FIELDS);
+        assert_eq!(calls[0].public_inputs[0][31], 0xab);
+        assert!(calls[0].public_inputs[0][..31].iter().all(|b| *b == 0));
+        assert_eq!(calls[0].public_inputs[PUBLIC_INPUT_FIELDS - 1][31], 4);
+    }
+
+    #[tokio::test]
+    async fn test_submit_fails_on_revert_or_drop() {
+        let outcomes = [
+            // Not a ProofHeir error
+            MockOutcome::Revert(Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef])),
+            MockOutcome::RevertedOnChain,
+            MockOutcome::Dropped,
+        ];
+
+        for outcome in outcomes {
+            let chain = Arc::new(MockChain::new(outcome.clone()));
+            let result = ChainSubmitter::new(chain.clone()).submit(&claim()).await;
+            assert!(
+                matches!(result, Err(NotaryError::OnChain(_))),
+                "{:?} should surface as ONCHAIN_FAILED",
+                outcome
+            );
+
+            // Reverts visible to eth_call never reach the chain
+            let sent = !matches!(outcome, MockOutcome::Revert(_));
+            assert_eq!(chain.simulations().len(), 1);
+            assert_eq!(chain.calls().len(), usize::from(sent));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_decodes_proof_heir_errors() {
+        let revert = ProofHeir::InvalidDataSource {
+            proofServerHash: [1; 32].into(),
+            trustedHash: [9; 32].into(),
+        }
+        .abi_encode();
+        let chain = Arc::new(MockChain::new(MockOutcome::Revert(revert.into())));
+
+        let result = ChainSubmitter::new(chain.clone()).submit(&claim()).await;
+        // Caught by the simulation
+        assert!(chain.calls().is_empty());
+        match result {
+            Err(NotaryError::ContractReverted(ProofHeirError::InvalidDataSource {
+                proof_server_hash,
+                trusted_hash,
+            })) => {
+                assert_eq!(proof_server_hash, [1; 32]);
+                assert_eq!(trusted_hash, [9; 32]);
+            }
+            other => panic!("expected InvalidDataSource, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settle_claim_modes() {
+        let chain = Arc::new(MockChain::default());
+        let submitter = ChainSubmitter::new(chain.clone());
+        let claim = claim();
+
+        let skipped = settle_claim(&claim, SubmissionMode::VerifyOnly, &submitter).await;
+        assert_eq!(skipped.unwrap(), Submission::Skipped);
+
+        let Submission::Calldata { to, data } =
+            settle_claim(&claim, SubmissionMode::Calldata, &submitter)
+                .await
+                .unwrap()
+        else {
+            panic!("expected calldata");
+        };
+        assert_eq!(to, TESTATOR);
+        let call = ProofHeir::proveDeathAndRegisterHeirCall::abi_decode(&data, true).unwrap();
+        assert_eq!(call.proof.as_ref(), claim.proof_body.as_slice());
+        assert_eq!(call.publicInputs, claim.solidity_public_inputs().unwrap());
+
+        // Neither mode touches the chain
+        assert!(chain.calls().is_empty());
+
+        // Dry run only simulates
+        let simulated = settle_claim(&claim, SubmissionMode::DryRun, &submitter).await;
+        assert_eq!(simulated.unwrap(), Submission::Simulated);
+        assert_eq!(chain.simulations().len(), 1);
+        assert!(chain.calls().is_empty());
+
+        let submitted = settle_claim(&claim, SubmissionMode::Submit, &submitter).await;
+        assert!(matches!(submitted, Ok(Submission::Submitted(_))));
+        assert_eq!(chain.calls().len(), 1);
+
+        // A dry run reports the decoded revert reason
+        chain.set_outcome(MockOutcome::Revert(
+            ProofHeir::IdentityNotRegistered {}.abi_encode().into(),
+        ));
+        let simulated = settle_claim(&claim, SubmissionMode::DryRun, &submitter).await;
+        assert!(matches!(
+            simulated,
+            Err(NotaryError::ContractReverted(
+                ProofHeirError::IdentityNotRegistered
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_submission_mode_serde() {
+        let mode: SubmissionMode = serde_json::from_str("\"verify_only\"").unwrap();
+        assert_eq!(mode, SubmissionMode::VerifyOnly);
+        assert_eq!(
+            serde_json::to_string(&SubmissionMode::Calldata).unwrap(),
+            "\"calldata\""
+        );
+        let mode: SubmissionMode = serde_json::from_str("\"dry_run\"").unwrap();
+        assert_eq!(mode, SubmissionMode::DryRun);
+    }
+}
@@ -0,0 +1,188 @@
+/// TLS trust configuration - root certificates and SPKI pins for the registry connection
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use k256::sha2::{Digest, Sha256};
+use tlsn::config::{CertificateDer, RootCertStore};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::error::{NotaryError, Result};
+
+/// Which certificates the prover and verifier accept for the registry
+///
+/// With no CA bundles configured the platform's native roots are used, as before.
+/// Pins are hex SHA256 digests of the leaf certificate's DER `SubjectPublicKeyInfo`;
+/// when any pin is set, every other key is refused.
+///
+/// Pinning is enforced by the prover only. The verifier authenticates the server identity
+/// inside tlsn's `Verifier::verify`, which checks the chain against [`Self::root_store`] but
+/// hands back only the server name, never the certificates, so there is no key to compare a
+/// pin with. A verifier that needs more than "some trusted CA vouched for this name" has to
+/// narrow its roots instead (`TLS_CA_BUNDLES` with the registry's CA, native roots off).
+#[derive(Debug, Clone, Default)]
+pub struct TlsTrust {
+    /// PEM files with CA certificates to trust
+    pub ca_bundles: Vec<PathBuf>,
+    /// Also trust the platform's native roots
+    pub native_roots: bool,
+    /// Accepted SPKI SHA256 digests (hex); empty disables pinning. Prover side only
+    pub spki_pins: Vec<String>,
+}
+
+impl TlsTrust {
+    /// Reads `TLS_CA_BUNDLES` and `TLS_SPKI_PINS` (comma-separated) and `TLS_NATIVE_ROOTS`
+    ///
+    /// Native roots default to on only when no CA bundle is configured.
+    pub fn from_env() -> Self {
+        let list = |name: &str| -> Vec<String> {
+            std::env::var(name)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(|item| item.trim().to_string())
+                        .filter(|item| !item.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let ca_bundles: Vec<PathBuf> = list("TLS_CA_BUNDLES").into_iter().map(PathBuf::from).collect();
+        let native_roots = std::env::var("TLS_NATIVE_ROOTS")
+            .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(ca_bundles.is_empty());
+
+        Self {
+            ca_bundles,
+            native_roots,
+            spki_pins: list("TLS_SPKI_PINS"),
+        }
+    }
+
+    /// Root store built from the configured CA bundles and, optionally, native roots
+    pub fn root_store(&self) -> Result<RootCertStore> {
+        let mut roots: Vec<CertificateDer> = Vec::new();
+
+        if self.native_roots {
+            let native_certs = rustls_native_certs::load_native_certs().map_err(|e| {
+                NotaryError::mpc_tls(format!("Failed to load platform certificates: {}", e))
+            })?;
+            roots.extend(native_certs.into_iter().map(|cert| CertificateDer(cert.0)));
+        }
+
+        for path in &self.ca_bundles {
+            let file = File::open(path).map_err(|e| {
+                NotaryError::invalid_input(format!("Cannot open CA bundle {}: {}", path.display(), e))
+            })?;
+            let certs = rustls_pemfile::certs(&mut BufReader::new(file)).map_err(|e| {
+                NotaryError::invalid_input(format!("Invalid PEM in {}: {}", path.display(), e))
+            })?;
+            if certs.is_empty() {
+                return Err(NotaryError::invalid_input(format!(
+                    "CA bundle {} contains no certificates",
+                    path.display()
+                )));
+            }
+            roots.extend(certs.into_iter().map(CertificateDer));
+        }
+
+        if roots.is_empty() {
+            return Err(NotaryError::invalid_input(
+                "No trusted root certificates: configure TLS_CA_BUNDLES or enable native roots",
+            ));
+        }
+
+        Ok(RootCertStore { roots })
+    }
+
+    /// HTTPS client for plain (non MPC-TLS) registry queries trusting the same roots
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().tls_built_in_root_certs(self.native_roots);
+        if !self.ca_bundles.is_empty() {
+            for root in self.root_store()?.roots {
+                let cert = reqwest::Certificate::from_der(&root.0).map_err(NotaryError::invalid_input)?;
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        builder.build().map_err(NotaryError::internal)
+    }
+
+    /// Check the server's leaf certificate against the configured pins
+    pub fn check_pins(&self, leaf_der: &[u8]) -> Result<()> {
+        if self.spki_pins.is_empty() {
+            return Ok(());
+        }
+
+        let fingerprint = hex::encode(spki_sha256(leaf_der)?);
+        if self
+            .spki_pins
+            .iter()
+            .any(|pin| pin.trim_start_matches("0x").eq_ignore_ascii_case(&fingerprint))
+        {
+            Ok(())
+        } else {
+            Err(NotaryError::UntrustedRegistry(format!(
+                "server key sha256 {} matches no configured SPKI pin",
+                fingerprint
+            )))
+        }
+    }
+}
+
+/// SHA256 of the DER `SubjectPublicKeyInfo` of a certificate
+pub fn spki_sha256(cert_der: &[u8]) -> Result<[u8; 32]> {
+    let (_, cert) = X509Certificate::from_der(cert_der)
+        .map_err(|e| NotaryError::mpc_tls(format!("Malformed server certificate: {}", e)))?;
+    Ok(Sha256::digest(cert.public_key().raw).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{generate_simple_self_signed, CertifiedKey};
+
+    fn self_signed() -> CertifiedKey {
+        generate_simple_self_signed(vec!["localhost".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn test_spki_pin_matches_certificate_key() {
+        let CertifiedKey { cert, key_pair } = self_signed();
+        let pin = hex::encode(Sha256::digest(key_pair.public_key_der()));
+
+        let trust = TlsTrust {
+            spki_pins: vec![pin],
+            ..TlsTrust::default()
+        };
+        assert!(trust.check_pins(cert.der()).is_ok());
+
+        let other = self_signed();
+        assert!(matches!(
+            trust.check_pins(other.cert.der()),
+            Err(NotaryError::UntrustedRegistry(_))
+        ));
+
+        // No pins: any certificate is accepted
+        assert!(TlsTrust::default().check_pins(other.cert.der()).is_ok());
+    }
+
+    #[test]
+    fn test_root_store_from_pem_bundle() {
+        let CertifiedKey { cert, .. } = self_signed();
+        let path = std::env::temp_dir().join(format!("proofheir-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, cert.pem()).unwrap();
+
+        let trust = TlsTrust {
+            ca_bundles: vec![path.clone()],
+            ..TlsTrust::default()
+        };
+        let store = trust.root_store().unwrap();
+        assert_eq!(store.roots.len(), 1);
+        assert_eq!(store.roots[0].0, cert.der().to_vec());
+
+        std::fs::remove_file(path).unwrap();
+
+        // Neither bundles nor native roots
+        assert!(TlsTrust::default().root_store().is_err());
+    }
+}
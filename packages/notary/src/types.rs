@@ -1,4 +1,8 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
+
+use crate::error::{NotaryError, Result};
 use tlsn::transcript::{hash::PlaintextHash, Direction, TranscriptCommitment};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -18,6 +22,88 @@ pub struct PublicInputs {
     pub server_hash: [u8; 32],
     pub id_commitment: [u8; 32],
     pub status_commitment: [u8; 32],
+    /// MPC-TLS commitment to the NUIP sent to the registry
+    pub nuip_commitment: [u8; 32],
+}
+
+impl PublicInputs {
+    /// Decode the public inputs embedded at the start of a noir-rs proof
+    ///
+    /// Each byte of every input is one 32-byte big-endian field element, in circuit order:
+    /// recipient(20) | server_hash(32) | id_commitment(32) | status_commitment(32) | nuip_commitment(32)
+    pub fn from_proof_fields(proof: &[u8]) -> Result<Self> {
+        let size = PUBLIC_INPUT_FIELDS * 32;
+        if proof.len() < size {
+            return Err(NotaryError::zk(format!(
+                "Proof too short to contain {} public inputs: {} bytes",
+                PUBLIC_INPUT_FIELDS,
+                proof.len()
+            )));
+        }
+
+        let mut bytes = Vec::with_capacity(PUBLIC_INPUT_FIELDS);
+        for (i, field) in proof[..size].chunks(32).enumerate() {
+            if field[..31].iter().any(|b| *b != 0) {
+                return Err(NotaryError::zk(format!(
+                    "Public input field {} is not a byte: 0x{}",
+                    i,
+                    hex::encode(field)
+                )));
+            }
+            bytes.push(field[31]);
+        }
+
+        let mut inputs = PublicInputs {
+            recipient: [0u8; 20],
+            server_hash: [0u8; 32],
+            id_commitment: [0u8; 32],
+            status_commitment: [0u8; 32],
+            nuip_commitment: [0u8; 32],
+        };
+        inputs.recipient.copy_from_slice(&bytes[0..20]);
+        inputs.server_hash.copy_from_slice(&bytes[20..52]);
+        inputs.id_commitment.copy_from_slice(&bytes[52..84]);
+        inputs.status_commitment.copy_from_slice(&bytes[84..116]);
+        inputs.nuip_commitment.copy_from_slice(&bytes[116..148]);
+        Ok(inputs)
+    }
+
+    /// Every field whose value differs between these (bundle) inputs and the ones decoded from the proof
+    pub fn mismatches(&self, from_proof: &PublicInputs) -> Vec<PublicInputMismatch> {
+        let fields: [(&'static str, &[u8], &[u8]); 5] = [
+            ("recipient", &self.recipient, &from_proof.recipient),
+            ("server_hash", &self.server_hash, &from_proof.server_hash),
+            ("id_commitment", &self.id_commitment, &from_proof.id_commitment),
+            ("status_commitment", &self.status_commitment, &from_proof.status_commitment),
+            ("nuip_commitment", &self.nuip_commitment, &from_proof.nuip_commitment),
+        ];
+
+        fields
+            .into_iter()
+            .filter(|(_, bundle, proof)| bundle != proof)
+            .map(|(field, bundle, proof)| PublicInputMismatch {
+                field: field.to_string(),
+                bundle: hex::encode(bundle),
+                proof: hex::encode(proof),
+            })
+            .collect()
+    }
+}
+
+/// A public input whose value in the bundle differs from the one embedded in the proof
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PublicInputMismatch {
+    pub field: String,
+    /// Hex value claimed in the `ZKProofBundle`
+    pub bundle: String,
+    /// Hex value the proof actually commits to
+    pub proof: String,
+}
+
+impl fmt::Display for PublicInputMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (bundle 0x{}, proof 0x{})", self.field, self.bundle, self.proof)
+    }
 }
 
 /// Proof bundle formatted for Solidity contract consumption
@@ -25,18 +111,36 @@ pub struct PublicInputs {
 pub struct SolidityProofBundle {
     /// ZK proof bytes (hex-encoded for JSON)
     pub proof: String,
-    /// Public inputs as array of 32-byte hex strings (116 fields total)
+    /// Public inputs as array of 32-byte hex strings (148 fields total)
     pub public_inputs: Vec<String>,
 }
 
+/// Number of public input fields in the circuit (one field per byte)
+/// recipient(20) + server_hash(32) + id_commitment(32) + status_commitment(32) + nuip_commitment(32)
+pub const PUBLIC_INPUT_FIELDS: usize = 148;
+
 // extract commitment from prover output
 pub fn received_commitments(
     transcript_commitments: &[TranscriptCommitment],
+) -> Vec<&PlaintextHash> {
+    commitments_in(transcript_commitments, Direction::Received)
+}
+
+// extract the sent (NUIP) commitment from prover output
+pub fn sent_commitments(
+    transcript_commitments: &[TranscriptCommitment],
+) -> Vec<&PlaintextHash> {
+    commitments_in(transcript_commitments, Direction::Sent)
+}
+
+fn commitments_in(
+    transcript_commitments: &[TranscriptCommitment],
+    direction: Direction,
 ) -> Vec<&PlaintextHash> {
     transcript_commitments
         .iter()
         .filter_map(|commitment| match commitment {
-            TranscriptCommitment::Hash(hash) if hash.direction == Direction::Received => Some(hash),
+            TranscriptCommitment::Hash(hash) if hash.direction == direction => Some(hash),
             _ => None,
         })
         .collect()
@@ -50,44 +154,73 @@ pub fn byte_to_field(byte: u8) -> [u8; 32] {
     field
 }
 
-/// Serializes public inputs to Solidity-compatible format (116 fields)
-/// Format: [recipient(20) | server_hash(32) | id_commitment(32) | status_commitment(32)]
-pub fn serialize_public_inputs_for_solidity(
-    recipient: [u8; 20],
-    server_hash: [u8; 32],
-    id_commitment: [u8; 32],
-    status_commitment: &[u8],
-) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    if status_commitment.len() != 32 {
-        return Err(format!("status_commitment must be 32 bytes, got {}", status_commitment.len()).into());
+/// Serializes public inputs to Solidity-compatible format (148 fields)
+/// Format: [recipient(20) | server_hash(32) | id_commitment(32) | status_commitment(32) | nuip_commitment(32)]
+pub fn serialize_public_inputs_for_solidity(inputs: &PublicInputs) -> Vec<String> {
+    let bytes = [
+        &inputs.recipient[..],
+        &inputs.server_hash,
+        &inputs.id_commitment,
+        &inputs.status_commitment,
+        &inputs.nuip_commitment,
+    ]
+    .concat();
+
+    // One field per byte
+    bytes
+        .into_iter()
+        .map(|byte| format!("0x{}", hex::encode(byte_to_field(byte))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_inputs() -> PublicInputs {
+        PublicInputs {
+            recipient: [0x11; 20],
+            server_hash: [0x22; 32],
+            id_commitment: [0x33; 32],
+            status_commitment: [0x44; 32],
+            nuip_commitment: [0x55; 32],
+        }
     }
-    
-    let mut fields = Vec::new();
-    
-    // Recipient (20 bytes → 20 fields)
-    for byte in recipient {
-        let field = byte_to_field(byte);
-        fields.push(format!("0x{}", hex::encode(field)));
+
+    fn proof_with(inputs: &PublicInputs) -> Vec<u8> {
+        let fields = serialize_public_inputs_for_solidity(inputs);
+        let mut proof: Vec<u8> = fields
+            .iter()
+            .flat_map(|f| hex::decode(f.trim_start_matches("0x")).unwrap())
+            .collect();
+        proof.extend_from_slice(&[0xAB; 64]); // proof body
+        proof
     }
-    
-    // Server hash (32 bytes → 32 fields)
-    for byte in server_hash {
-        let field = byte_to_field(byte);
-        fields.push(format!("0x{}", hex::encode(field)));
+
+    #[test]
+    fn test_public_inputs_roundtrip_through_proof() {
+        let inputs = sample_inputs();
+        let decoded = PublicInputs::from_proof_fields(&proof_with(&inputs)).unwrap();
+        assert!(inputs.mismatches(&decoded).is_empty());
     }
-    
-    // ID commitment (32 bytes → 32 fields)
-    for byte in id_commitment {
-        let field = byte_to_field(byte);
-        fields.push(format!("0x{}", hex::encode(field)));
+
+    #[test]
+    fn test_public_inputs_mismatch_reported_per_field() {
+        let inputs = sample_inputs();
+        let mut tampered = inputs.clone();
+        tampered.recipient[0] = 0x99;
+        tampered.id_commitment[31] = 0x00;
+
+        let decoded = PublicInputs::from_proof_fields(&proof_with(&tampered)).unwrap();
+        let fields: Vec<_> = inputs.mismatches(&decoded).iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, vec!["recipient", "id_commitment"]);
     }
-    
-    // Status commitment (32 bytes → 32 fields)
-    for byte in status_commitment {
-        let field = byte_to_field(*byte);
-        fields.push(format!("0x{}", hex::encode(field)));
+
+    #[test]
+    fn test_public_inputs_reject_non_byte_field() {
+        let mut proof = proof_with(&sample_inputs());
+        proof[0] = 1; // high byte of the first field
+        assert!(PublicInputs::from_proof_fields(&proof).is_err());
+        assert!(PublicInputs::from_proof_fields(&proof[..100]).is_err());
     }
-    
-    assert_eq!(fields.len(), 116, "Public inputs must have exactly 116 fields");
-    Ok(fields)
 }
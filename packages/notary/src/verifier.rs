@@ -1,8 +1,19 @@
-use crate::types::{received_commitments, serialize_public_inputs_for_solidity, ZKProofBundle};
-use noir::barretenberg::verify::{get_ultra_honk_verification_key};
-use serde_json::Value;
+use crate::circuit::CircuitContext;
+use crate::error::{NotaryError, Result};
+use crate::inputs::RegistryDomain;
+use crate::progress::{Progress, ProofStage};
+use crate::proving::ProvingPool;
+use crate::registry::{ColombiaRegistry, RegistryAdapter};
+use crate::chain::ProofHeir;
+use crate::submitter::{settle_claim, ChainSubmitter, ClaimSubmitter, Submission, SubmissionMode};
+use crate::tls::TlsTrust;
+use crate::types::{
+    received_commitments, sent_commitments,
+    serialize_public_inputs_for_solidity, PublicInputs, ZKProofBundle,
+};
+use noir::barretenberg::verify::verify_ultra_honk_keccak;
 use tlsn::{
-    config::{CertificateDer, ProtocolConfigValidator, RootCertStore},
+    config::ProtocolConfigValidator,
     connection::ServerName,
     hash::HashAlgId,
     transcript::{Direction, PartialTranscript},
@@ -11,54 +22,158 @@ use tlsn::{
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
 use tokio_util::compat::TokioAsyncReadCompatExt;
 use tracing::instrument;
-use alloy::{
-    network::EthereumWallet,
-    primitives::{Address, Bytes, FixedBytes},
-    providers::ProviderBuilder,
-    signers::local::PrivateKeySigner,
-    sol,
-};
+use alloy::primitives::{Bytes, B256};
+use alloy::sol_types::SolCall;
+use hyper::Uri;
+use k256::sha2::{Digest, Sha256};
+use serde::de::{Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::Value;
 use std::env;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
 
 // Constants from prover
 const MAX_SENT_DATA: usize = 1 << 12;
 const MAX_RECV_DATA: usize = 1 << 14;
 
-// Define the ProofHeir contract interface
-sol! {
-    #[sol(rpc)]
-    contract ProofHeir {
-        function proveDeathAndRegisterHeir(bytes calldata proof, bytes32[] calldata publicInputs) external;
-        
-        event HeirRegistered(address indexed owner, address indexed heir);
+/// Largest `ZKProofBundle` accepted from a prover (VK, proof and public inputs take ~25 KB)
+pub const MAX_BUNDLE_LEN: usize = 1 << 20;
+
+/// Expectations the verifier enforces on the prover's MPC-TLS session
+#[derive(Clone)]
+pub struct VerifierOptions {
+    /// Registry whose endpoint the prover must have queried
+    pub registry: Arc<dyn RegistryAdapter>,
+    /// Registry domains the verifier accepts; `None` accepts only the domain of [`Self::registry`]
+    pub allowed_domains: Option<Vec<String>>,
+    /// Root certificates the registry's certificate must chain to (SPKI pins are not checked here)
+    pub tls: TlsTrust,
+    /// What [`verifier`] does with a verified claim
+    pub submission: SubmissionMode,
+    /// Where [`verifier`] sends verified claims in [`SubmissionMode::Submit`]
+    pub submitter: Arc<dyn ClaimSubmitter>,
+    /// Reports the on-chain transaction stage of an in-process proof run
+    pub progress: Progress,
+    /// Where proof verification runs, off the async runtime
+    pub proving: ProvingPool,
+}
+
+impl VerifierOptions {
+    /// Default options: Colombian registry as configured by the environment,
+    /// allowlist from `NOTARY_ALLOWED_DOMAINS` (comma-separated), roots from [`TlsTrust::from_env`],
+    /// on-chain submission from [`ChainSubmitter::from_env`], the process-wide [`ProvingPool`]
+    pub fn from_env() -> Self {
+        let allowed_domains = env::var("NOTARY_ALLOWED_DOMAINS").ok().map(|domains| {
+            domains
+                .split(',')
+                .map(|domain| domain.trim().to_string())
+                .filter(|domain| !domain.is_empty())
+                .collect()
+        });
+
+        Self {
+            registry: Arc::new(ColombiaRegistry::from_env()),
+            allowed_domains,
+            tls: TlsTrust::from_env(),
+            submission: SubmissionMode::default(),
+            submitter: Arc::new(ChainSubmitter::from_env()),
+            progress: Progress::default(),
+            proving: ProvingPool::global().clone(),
+        }
+    }
+}
+
+/// A claim whose MPC-TLS session and ZK proof have been fully verified
+#[derive(Debug, Clone)]
+pub struct VerifiedClaim {
+    /// Testator's delegated account the claim is for
+    pub testator_address: [u8; 20],
+    /// Registry domain authenticated over MPC-TLS
+    pub server_name: String,
+    /// Public inputs decoded from the proof itself
+    pub public_inputs: PublicInputs,
+    /// Proof without its public-input prefix, as `proveDeathAndRegisterHeir` expects it
+    pub proof_body: Vec<u8>,
+    /// Data revealed by the prover
+    pub transcript: PartialTranscript,
+}
+
+impl VerifiedClaim {
+    /// Public inputs as the `bytes32[]` argument of `proveDeathAndRegisterHeir` (148 fields)
+    pub fn solidity_public_inputs(&self) -> Result<Vec<B256>> {
+        let public_inputs_hex = serialize_public_inputs_for_solidity(&self.public_inputs);
+
+        public_inputs_hex
+            .iter()
+            .map(|hex_str| {
+                let bytes = hex::decode(hex_str.trim_start_matches("0x")).map_err(NotaryError::internal)?;
+                Ok(B256::from_slice(&bytes))
+            })
+            .collect()
     }
+
+    /// ABI-encoded `proveDeathAndRegisterHeir(proof, publicInputs)` call for the testator's delegated account
+    pub fn calldata(&self) -> Result<Vec<u8>> {
+        let call = ProofHeir::proveDeathAndRegisterHeirCall {
+            proof: Bytes::copy_from_slice(&self.proof_body),
+            publicInputs: self.solidity_public_inputs()?,
+        };
+        Ok(call.abi_encode())
+    }
+}
+
+/// A verified session and what was done with its claim
+#[derive(Debug, Clone)]
+pub struct VerifiedSession {
+    pub transcript: PartialTranscript,
+    pub submission: Submission,
 }
 
-#[instrument(skip(socket, extra_socket))]
+/// Verify the prover's session, then submit the claim or return its calldata as `options.submission` says
+#[instrument(skip(socket, extra_socket, options))]
 pub async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
+    socket: T,
+    extra_socket: T,
+    testator_address: [u8; 20],
+    options: &VerifierOptions,
+) -> Result<VerifiedSession> {
+    let claim = verify_attestation(socket, extra_socket, testator_address, options).await?;
+    if matches!(options.submission, SubmissionMode::Submit | SubmissionMode::DryRun) {
+        options.progress.stage(ProofStage::Transaction);
+    }
+    let submission = settle_claim(&claim, options.submission, options.submitter.as_ref()).await?;
+
+    Ok(VerifiedSession {
+        transcript: claim.transcript,
+        submission,
+    })
+}
+
+/// Verify the prover's MPC-TLS session and ZK proof without touching the chain
+///
+/// Checks the registry endpoint and commitments, verifies the UltraHonk proof locally and
+/// cross-checks its public inputs against the bundle and the MPC-TLS commitments.
+#[instrument(skip(socket, extra_socket, options))]
+pub async fn verify_attestation<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
     socket: T,
     mut extra_socket: T,
     testator_address: [u8; 20],
-) -> Result<PartialTranscript, Box<dyn std::error::Error>> {
+    options: &VerifierOptions,
+) -> Result<VerifiedClaim> {
     // Limits matching prover
     let protocol_config_validator = ProtocolConfigValidator::builder()
         .max_sent_data(MAX_SENT_DATA)
         .max_recv_data(MAX_RECV_DATA)
-        .build()?;
-
-    // Use native root certificates (matching Prover)
-    let native_certs = rustls_native_certs::load_native_certs()
-        .map_err(|e| format!("Failed to load platform certificates: {}", e))?;
-        
-    let roots: Vec<CertificateDer> = native_certs
-        .into_iter()
-        .map(|cert| CertificateDer(cert.0))
-        .collect();
-        
+        .build()
+        .map_err(NotaryError::mpc_tls)?;
+
+    // Trusted roots: configured CA bundles and/or native roots (same settings as the prover)
     let verifier_config = VerifierConfig::builder()
-        .root_store(RootCertStore { roots })
+        .root_store(options.tls.root_store()?)
         .protocol_config_validator(protocol_config_validator)
-        .build()?;
+        .build()
+        .map_err(NotaryError::mpc_tls)?;
 
     let verifier = Verifier::new(verifier_config);
 
@@ -70,22 +185,25 @@ pub async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>
         ..
     } = verifier
         .verify(socket.compat(), &VerifyConfig::default())
-        .await?;
+        .await
+        .map_err(NotaryError::mpc_tls)?;
 
-    let server_name = server_name.ok_or("Prover should have revealed server name")?;
-    let transcript = transcript.ok_or("Prover should have revealed transcript data")?;
+    let server_name =
+        server_name.ok_or_else(|| NotaryError::mpc_tls("Prover should have revealed server name"))?;
+    let transcript = transcript
+        .ok_or_else(|| NotaryError::mpc_tls("Prover should have revealed transcript data"))?;
     
     // Check received data commitment (MPC-TLS level)
     let received_commitments = received_commitments(&transcript_commitments);
     let received_commitment = received_commitments
         .first()
-        .ok_or("Missing received hash commitment")?;
+        .ok_or_else(|| NotaryError::commitment("Missing received hash commitment"))?;
 
     if received_commitment.direction != Direction::Received {
-        return Err("Commitment direction mismatch".into());
+        return Err(NotaryError::commitment("Commitment direction mismatch"));
     }
     if received_commitment.hash.alg != HashAlgId::SHA256 {
-         return Err("Commitment hash algo mismatch".into());
+         return Err(NotaryError::commitment("Commitment hash algo mismatch"));
     }
 
     let committed_hash = &received_commitment.hash;
@@ -93,226 +211,420 @@ pub async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>
 
     // Verify Server Name (Civil Registry Mock)
     let ServerName::Dns(server_name_str) = server_name;
-    // Note: We don't hardcode the check here strictly, but we log it
     tracing::info!("Verifier connected to server name: {}", server_name_str.as_str());
 
-    // Receive ZKProof information from prover via extra socket
+    let server_domain = check_server_name(server_name_str.as_str(), options)?;
+
+    // The national ID must be committed, not revealed, and the rest of the
+    // request must target the registry endpoint
+    let sent_commitments = sent_commitments(&transcript_commitments);
+    let sent_commitment = sent_commitments
+        .first()
+        .ok_or_else(|| NotaryError::commitment("Missing sent hash commitment (national ID)"))?;
+    if sent_commitment.hash.alg != HashAlgId::SHA256 {
+        return Err(NotaryError::commitment("Sent commitment hash algo mismatch"));
+    }
+
+    check_request(
+        transcript.sent_unsafe(),
+        options.registry.url(),
+        server_name_str.as_str(),
+    )?;
+
+    let id_start = sent_commitment
+        .idx
+        .min()
+        .ok_or_else(|| NotaryError::commitment("Empty sent commitment"))?;
+    let id_end = sent_commitment
+        .idx
+        .end()
+        .ok_or_else(|| NotaryError::commitment("Empty sent commitment"))?;
+    check_id_commitment_span(
+        transcript.sent_unsafe(),
+        id_start..id_end,
+        options.registry.id_field(),
+    )?;
+
+    let expected_nuip_hash_bytes = sent_commitment.hash.value.as_bytes().to_vec();
+
+    // Receive ZKProof information from prover via extra socket, never more than a bundle can be
     let mut buf = Vec::new();
-    extra_socket.read_to_end(&mut buf).await?;
+    (&mut extra_socket)
+        .take(MAX_BUNDLE_LEN as u64 + 1)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(NotaryError::mpc_tls)?;
+    if buf.len() > MAX_BUNDLE_LEN {
+        return Err(NotaryError::zk(format!(
+            "ZK proof bundle exceeds {} bytes",
+            MAX_BUNDLE_LEN
+        )));
+    }
 
     if buf.is_empty() {
-        return Err("No ZK proof data received from prover".into());
+        return Err(NotaryError::zk("No ZK proof data received from prover"));
     }
 
     let msg: ZKProofBundle = bincode::deserialize(&buf)
-        .map_err(|e| format!("Failed to deserialize ZK proof bundle: {}", e))?;
-
-    // Verify ZK proof
-    // Load bytecode from pre-computed path or include string if prefered
-    const PROGRAM_JSON: &str = include_str!("../circuits.json");
-    let json: Value = serde_json::from_str(PROGRAM_JSON)?;
-    let bytecode = json["bytecode"]
-        .as_str()
-        .ok_or("bytecode field not found in program.json")?;
-
-    // Ideally we should use the pre-computed VK from file to match prover
-    // But generating it ensures we are checking against the source truth
-    let vk = get_ultra_honk_verification_key(bytecode, false)?;
-
-    if vk != msg.vk {
-        return Err("Verification key mismatch: Prover used a different key than Verifier expected".into());
+        .map_err(|e| NotaryError::zk(format!("Failed to deserialize ZK proof bundle: {}", e)))?;
+
+    // The claimed server hash must be the one of the server authenticated over MPC-TLS
+    let expected_server_hash = server_domain.server_hash();
+    if msg.public_inputs.server_hash != expected_server_hash {
+        tracing::error!(
+            "❌ server_hash does not match TLS server name '{}'.\nBundle: {}\nExpected: {}",
+            server_name_str.as_str(),
+            hex::encode(msg.public_inputs.server_hash),
+            hex::encode(expected_server_hash)
+        );
+        return Err(NotaryError::commitment(format!(
+            "server_hash does not match the TLS-authenticated server name '{}'",
+            server_name_str.as_str()
+        )));
     }
 
     let proof = msg.proof;
 
-    // Validate Status Commitment in Public Inputs
-    // Public Inputs Input Structure (as bytes in proof):
-    // 1. Recipient: [u8; 20] -> 20 field elements (32 bytes each) = 640 bytes
-    // 2. Server Hash: [u8; 32] -> 32 field elements = 1024 bytes
-    // 3. ID Commitment: [u8; 32] -> 32 field elements = 1024 bytes
-    // 4. Status Commitment: [u8; 32] -> 32 field elements = 1024 bytes
-    
-    // Start index for status_commitment = (20 + 32 + 32) * 32 = 84 * 32 = 2688 bytes
-    let start_offset = (20 + 32 + 32) * 32;
-    let end_offset = start_offset + (32 * 32);
+    // Verify ZK proof - Barretenberg blocks: verify on the proving pool
+    let bundle_vk = msg.vk;
+    let checked_proof = proof.clone();
+    let (layout, proof_valid) = options
+        .proving
+        .run(&Progress::default(), move || {
+            // The circuit's own VK (computed once per process), never the one the prover sent
+            let circuit = CircuitContext::global()?;
+
+            if circuit.vk() != bundle_vk.as_slice() {
+                return Err(NotaryError::zk(
+                    "Verification key mismatch: Prover used a different key than Verifier expected",
+                ));
+            }
 
-    if proof.len() < end_offset {
-        return Err("Proof too short to contain status commitment".into());
+            // Cryptographically verify the proof against the pinned VK before any gas is spent
+            let valid = verify_ultra_honk_keccak(checked_proof, circuit.vk().to_vec(), false)
+                .map_err(|e| {
+                    NotaryError::InvalidProof(format!("UltraHonk verification error: {}", e))
+                })?;
+            Ok((circuit.layout(), valid))
+        })
+        .await?;
+
+    if !proof_valid {
+        tracing::error!("❌ ZK proof failed local UltraHonk verification");
+        return Err(NotaryError::InvalidProof(
+            "UltraHonk proof does not verify against the pinned verification key".to_string(),
+        ));
     }
 
-    // Extract status commitment from proof
-    // Each byte of the original [u8; 32] is encoded as a 32-byte Field Element (BigEndian). 
-    // Since the value is a u8, it's just the last byte of the 32-byte chunk.
-    let status_commitment_from_proof: Vec<u8> = proof[start_offset..end_offset]
-        .chunks(32)
-        .map(|chunk: &[u8]| *chunk.last().unwrap_or(&0))
-        .collect();
+    tracing::info!("✅ ZK proof verified locally (UltraHonk, keccak)");
+
+    // Split at the public-input boundary given by the circuit ABI
+    let split = layout.split(&proof)?;
+    tracing::info!(
+        "Proof layout: {} public input fields + {} body bytes",
+        layout.public_input_fields(),
+        split.body.len()
+    );
 
-    // Verify match
-    if status_commitment_from_proof != expected_status_hash_bytes {
+    // Decode every public input the proof commits to and compare with the bundle
+    // Layout: recipient(20) | server_hash(32) | id_commitment(32) | status_commitment(32) | nuip_commitment(32)
+    // Each byte is a 32-byte big-endian field element
+    let proof_inputs = PublicInputs::from_proof_fields(split.public_inputs)?;
+
+    let mismatches = msg.public_inputs.mismatches(&proof_inputs);
+    if !mismatches.is_empty() {
+        for mismatch in &mismatches {
+            tracing::error!("❌ ZK Proof Public Input mismatch: {}", mismatch);
+        }
+        return Err(NotaryError::PublicInputsMismatch(mismatches));
+    }
+
+    // Verify match against MPC-TLS commitments
+    if proof_inputs.status_commitment.as_slice() != expected_status_hash_bytes.as_slice() {
          tracing::error!(
             "❌ ZK Proof Public Input 'Status Commitment' does not match MPC-TLS commitment.\nZK: {}\nMPC: {}", 
-            hex::encode(&status_commitment_from_proof),
+            hex::encode(proof_inputs.status_commitment),
             hex::encode(&expected_status_hash_bytes)
         );
-        return Err("Hash in proof does not match committed hash in MPC-TLS".into());
+        return Err(NotaryError::commitment(
+            "Hash in proof does not match committed hash in MPC-TLS",
+        ));
     }
-    
+
+    if proof_inputs.nuip_commitment.as_slice() != expected_nuip_hash_bytes.as_slice() {
+        tracing::error!(
+            "❌ ZK Proof Public Input 'NUIP Commitment' does not match MPC-TLS commitment.\nZK: {}\nMPC: {}",
+            hex::encode(proof_inputs.nuip_commitment),
+            hex::encode(&expected_nuip_hash_bytes)
+        );
+        return Err(NotaryError::commitment(
+            "NUIP commitment in proof does not match the NUIP committed in MPC-TLS",
+        ));
+    }
+
     tracing::info!("✅ ZK Proof Public Input matches MPC-TLS commitment!");
 
-    // ========================================================================
-    // SEND TRANSACTION TO VERIFY PROOF AND REGISTER HEIR ON-CHAIN
-    // ========================================================================
-    
-    // Read configuration from environment
-    let rpc_url = env::var("RPC_URL")
-        .unwrap_or_else(|_| "http://localhost:8545".to_string());
-    let private_key = env::var("VERIFIER_PRIVATE_KEY")
-        .map_err(|_| "VERIFIER_PRIVATE_KEY not set in environment")?;
-    
-    // Convert testator address to Address type
-    let testator_addr = Address::from_slice(&testator_address);
-    
-    tracing::info!("📡 Calling proveDeathAndRegisterHeir on delegated account: 0x{}", hex::encode(&testator_address));
-    tracing::info!("🔗 RPC URL: {}", rpc_url);
-    
-    // Create signer from private key
-    let signer: PrivateKeySigner = private_key.parse()
-        .map_err(|e| format!("Invalid private key: {}", e))?;
-    
-    let wallet = EthereumWallet::from(signer);
-    
-    tracing::info!("🔑 Using verifier wallet: {}", wallet.default_signer().address());
-    
-    // Create provider with signer
-    let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
-        .on_http(rpc_url.parse()?);
-    
-    // Serialize public inputs to Solidity format (116 fields)
-    let public_inputs_hex = serialize_public_inputs_for_solidity(
-        msg.public_inputs.recipient,
-        msg.public_inputs.server_hash,
-        msg.public_inputs.id_commitment,
-        &status_commitment_from_proof,
-    )?;
-    
-    // Convert to FixedBytes<32> array for contract call
-    let public_inputs_bytes: Vec<FixedBytes<32>> = public_inputs_hex
-        .iter()
-        .map(|hex_str| {
-            let hex_str = hex_str.trim_start_matches("0x");
-            let bytes = hex::decode(hex_str).expect("Invalid hex in public inputs");
-            FixedBytes::<32>::from_slice(&bytes)
-        })
-        .collect();
-    
-    // Convert proof to Bytes
-    
-    // ------------------------------------------------------------------------
-    // HANDLING PROOF SIZE MISMATCH (noir-rs vs Solidity Verifier)
-    // ------------------------------------------------------------------------
-    
-    // We calculate the number of public input fields dynamically based on the 
-    // serialization logic defined in `types.rs`. This ensures that if the 
-    // circuit inputs change, this logic adapts automatically.
-    
-    // Temporarily serialize inputs using the commitment provided in the bundle
-    // (We certify that this matches the proof later)
-    let temp_public_inputs_hex = serialize_public_inputs_for_solidity(
-        msg.public_inputs.recipient,
-        msg.public_inputs.server_hash,
-        msg.public_inputs.id_commitment,
-        &msg.public_inputs.status_commitment,
-    )?;
-    
-    let public_inputs_field_count = temp_public_inputs_hex.len();
-    let public_inputs_size_bytes = public_inputs_field_count * 32;
-    
-    // Check if proof contains concatenated public inputs
-    let proof_size_elements = proof.len() / 32;
-    
-    // If the proof is significantly larger than the inputs, and matches the 
-    // pattern of "Inputs + Proof", we slice. 
-    // (We don't hardcode 508 here, but assume anything larger than inputs 
-    // by a reasonable margin (e.g. > 1KB) likely includes the proof)
-    
-    let final_proof = if proof.len() > public_inputs_size_bytes && proof.len() % 32 == 0 {
-         // Heuristic: If proof starts with public inputs? 
-         // Since we can't easily verify the "Proof" part without verification,
-         // we assume that if it's the "Concatenated" style, it will be:
-         // Size = PublicInputsSize + PureProofSize
-         
-         // For safety against regression where noir-rs might NOT concatenate, 
-         // we check if slicing would leave a "valid-looking" proof size.
-         // UltraHonk proofs are usually around ~16KB (500+ fields).
-         
-         if proof.len() > public_inputs_size_bytes + 10000 { 
-             tracing::info!("💡 Detected Concatenated Proof ({} elements).", proof_size_elements);
-             tracing::info!("   Public Inputs count: {} elements.", public_inputs_field_count);
-             tracing::info!("   Action: Slicing off first {} bytes.", public_inputs_size_bytes);
-             
-             proof[public_inputs_size_bytes..].to_vec()
-         } else {
-             proof.clone()
-         }
-    } else {
-        proof.clone()
+    Ok(VerifiedClaim {
+        testator_address,
+        server_name: server_name_str.as_str().to_string(),
+        public_inputs: proof_inputs,
+        proof_body: split.body.to_vec(),
+        transcript,
+    })
+}
+
+/// The TLS-authenticated server name as a registry domain, if the notary accepts it
+///
+/// Without `allowed_domains`, only the configured registry's own domain is accepted; otherwise a
+/// proof against any server with a valid certificate would be notarized.
+fn check_server_name(server_name: &str, options: &VerifierOptions) -> Result<RegistryDomain> {
+    let allowed = match &options.allowed_domains {
+        Some(allowed_domains) => allowed_domains
+            .iter()
+            .any(|domain| domain.eq_ignore_ascii_case(server_name)),
+        None => options
+            .registry
+            .domain()?
+            .as_str()
+            .eq_ignore_ascii_case(server_name),
     };
-    
-    let proof_bytes = Bytes::from(final_proof.clone()); // Use final_proof for transaction
+    if !allowed {
+        return Err(NotaryError::UntrustedRegistry(format!(
+            "'{}' is not in the registry allowlist",
+            server_name
+        )));
+    }
 
-    // Validate Status Commitment extraction from the ORIGINAL full proof or the FINAL proof?
-    // If we sliced it, the final_proof does NOT contain the public inputs anymore.
-    // So we can't extract them from `final_proof`.
-    // But we need to verify that the proof actually "binds" those inputs.
-    
-    // Wait, if `proof` (the original) has inputs concatenated, they are at the beginning.
-    // The PROOF ITSELF (the zk part) doesn't "contain" the inputs in plain text, 
-    // it contains cryptographic commitments to them.
-    
-    // The logic below (lines 137+) was trying to extract `status_commitment` from 
-    // the proof by assuming offsets.
-    // `start_offset = (20 + 32 + 32) * 32` -> This offset is into the PUBLIC INPUTS section.
-    // So we MUST use the `proof` (original concatenated variable) to extract this check, 
-    // NOT `final_proof`.
-    
-    // ... existing logic uses `proof` variable ...
-    
-    // Create contract instance pointing to the TESTATOR'S DELEGATED ACCOUNT
-    // This is the key change: we call the function on the testator's account,
-    // not on the ProofHeir contract address directly
-    let contract = ProofHeir::new(testator_addr, &provider);
-    
-    // Send transaction to register heir on-chain
-    tracing::info!("🔐 Sending transaction: proveDeathAndRegisterHeir()...");
-    
-    // Let the provider estimate gas (required for Mantle's L1+L2 cost calculation)
-    // Mantle requires estimateGas to calculate l1Cost correctly
-    let tx_builder = contract.proveDeathAndRegisterHeir(proof_bytes, public_inputs_bytes);
-    
-    // Send the transaction
-    let pending_tx = tx_builder.send().await
-        .map_err(|e| format!("Failed to send transaction: {}", e))?;
-    
-    let tx_hash = *pending_tx.tx_hash();
-    tracing::info!("📝 Transaction sent! Hash: {:?}", tx_hash);
-    
-    // Wait for transaction confirmation
-    tracing::info!("⏳ Waiting for transaction confirmation...");
-    let receipt = pending_tx.get_receipt().await
-        .map_err(|e| format!("Failed to get transaction receipt: {}", e))?;
-    
-    if receipt.status() {
-        tracing::info!("✅ Transaction confirmed in block: {}", receipt.block_number.unwrap_or(0));
-        tracing::info!("✅ On-chain proof verification succeeded!");
-        tracing::info!("✅ Heir registered in testator's delegated account storage!");
-        tracing::info!("⛽ Gas used: {}", receipt.gas_used);
-    } else {
-        tracing::error!("❌ Transaction reverted!");
-        return Err("Transaction was reverted by the contract".into());
+    // A name the circuit cannot hold is the server's problem, not invalid input from the client
+    RegistryDomain::new(server_name).map_err(|e| NotaryError::UntrustedRegistry(e.to_string()))
+}
+
+/// Checks the revealed request head: method, path and Host must match the registry endpoint
+fn check_request(sent: &[u8], registry_url: &str, server_name: &str) -> Result<()> {
+    let expected = registry_url
+        .parse::<Uri>()
+        .map_err(NotaryError::invalid_input)?;
+
+    let head_end = sent
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| NotaryError::mpc_tls("Sent data is not an HTTP request"))?;
+    let head = std::str::from_utf8(&sent[..head_end])
+        .map_err(|_| NotaryError::mpc_tls("HTTP request head is not valid UTF-8"))?;
+
+    // Redacted bytes show up as zeros in the partial transcript
+    if head.contains('\0') {
+        return Err(NotaryError::mpc_tls("HTTP request head was not fully revealed"));
+    }
+
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+
+    if method != "POST" {
+        return Err(NotaryError::mpc_tls(format!(
+            "Unexpected request method '{}', expected POST",
+            method
+        )));
+    }
+
+    // Target is either origin-form ("/path") or absolute-form ("https://host/path")
+    let target = target
+        .parse::<Uri>()
+        .map_err(|_| NotaryError::mpc_tls(format!("Invalid request target '{}'", target)))?;
+    if target.path() != expected.path() {
+        return Err(NotaryError::mpc_tls(format!(
+            "Unexpected request path '{}', expected '{}'",
+            target.path(),
+            expected.path()
+        )));
+    }
+
+    let host = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("host"))
+        .map(|(_, value)| value.trim())
+        .ok_or_else(|| NotaryError::mpc_tls("Request has no Host header"))?;
+    if host != server_name {
+        return Err(NotaryError::mpc_tls(format!(
+            "Host header '{}' does not match server name '{}'",
+            host, server_name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that the committed sent range is exactly the JSON value of `id_field`,
+/// so the commitment binds the national ID the registry was queried with
+///
+/// The revealed body is parsed with the committed range replaced by a placeholder the prover
+/// cannot predict (derived from the transcript itself). The body must have exactly one top-level
+/// `id_field` key and its value must be that placeholder: a second key, which the registry would
+/// read instead (last key wins), or a commitment over any other bytes is rejected.
+fn check_id_commitment_span(sent: &[u8], range: Range<usize>, id_field: &str) -> Result<()> {
+    let body_start = sent
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|head_end| head_end + 4)
+        .ok_or_else(|| NotaryError::mpc_tls("Sent data is not an HTTP request"))?;
+
+    if range.is_empty() || range.start < body_start || range.end > sent.len() {
+        return Err(NotaryError::commitment("Sent commitment range out of bounds"));
+    }
+
+    let placeholder = hex::encode(Sha256::digest(sent));
+    let mut body = sent[body_start..range.start].to_vec();
+    body.extend_from_slice(format!("\"{}\"", placeholder).as_bytes());
+    body.extend_from_slice(&sent[range.end..]);
+
+    let mut deserializer = serde_json::Deserializer::from_slice(&body);
+    let values = (&mut deserializer)
+        .deserialize_map(FieldValues(id_field))
+        .and_then(|values| deserializer.end().map(|()| values))
+        .map_err(|e| NotaryError::commitment(format!("Revealed request body is not valid JSON: {}", e)))?;
+
+    match values.as_slice() {
+        [Value::String(value)] if *value == placeholder => Ok(()),
+        [_] => Err(NotaryError::commitment(format!(
+            "Sent commitment does not cover the whole '{}' value",
+            id_field
+        ))),
+        [] => Err(NotaryError::commitment(format!(
+            "Request body has no top-level '{}' field",
+            id_field
+        ))),
+        _ => Err(NotaryError::commitment(format!(
+            "Request body has {} '{}' fields, expected exactly one",
+            values.len(),
+            id_field
+        ))),
+    }
+}
+
+/// Every value of one top-level key of a JSON object, duplicates included
+struct FieldValues<'a>(&'a str);
+
+impl<'de> Visitor<'de> for FieldValues<'_> {
+    type Value = Vec<Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.0 {
+                values.push(map.next_value::<Value>()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(values)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://registry.example.com/VigenciaCedula/consulta";
+
+    fn request(method: &str, target: &str, host: &str) -> Vec<u8> {
+        format!(
+            "{} {} HTTP/1.1\r\nhost: {}\r\ncontent-type: application/json\r\n\r\n{{\"nuip\":\0\0\0,\"ip\":\"1.2.3.4\"}}",
+            method, target, host
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_check_request_accepts_registry_endpoint() {
+        let sent = request("POST", "/VigenciaCedula/consulta", "registry.example.com");
+        assert!(check_request(&sent, URL, "registry.example.com").is_ok());
+
+        let sent = request("POST", URL, "registry.example.com");
+        assert!(check_request(&sent, URL, "registry.example.com").is_ok());
+    }
+
+    #[test]
+    fn test_check_request_rejects_wrong_method_path_or_host() {
+        let sent = request("GET", "/VigenciaCedula/consulta", "registry.example.com");
+        assert!(check_request(&sent, URL, "registry.example.com").is_err());
 
-    Ok(transcript)
+        let sent = request("POST", "/admin/update-status", "registry.example.com");
+        assert!(check_request(&sent, URL, "registry.example.com").is_err());
+
+        let sent = request("POST", "/VigenciaCedula/consulta", "evil.example.com");
+        assert!(check_request(&sent, URL, "registry.example.com").is_err());
+    }
+
+    fn options(allowed_domains: Option<Vec<String>>) -> VerifierOptions {
+        VerifierOptions {
+            registry: Arc::new(ColombiaRegistry::new(URL)),
+            allowed_domains,
+            ..VerifierOptions::from_env()
+        }
+    }
+
+    #[test]
+    fn test_server_name_defaults_to_registry_domain() {
+        let options = options(None);
+        let domain = check_server_name("Registry.Example.com", &options).unwrap();
+        assert_eq!(domain.as_str(), "Registry.Example.com");
+
+        let error = check_server_name("evil.example.com", &options).unwrap_err();
+        assert_eq!(error.code(), "UNTRUSTED_REGISTRY");
+    }
+
+    #[test]
+    fn test_server_name_allowlist() {
+        let options = options(Some(vec!["other.example.com".to_string()]));
+        assert!(check_server_name("other.example.com", &options).is_ok());
+        // An explicit allowlist replaces the registry default
+        assert!(check_server_name("registry.example.com", &options).is_err());
+
+        // Longer than the circuit's str<40>: untrusted, not invalid input
+        let long = format!("{}.example.com", "a".repeat(40));
+        let options = self::options(Some(vec![long.clone()]));
+        let error = check_server_name(&long, &options).unwrap_err();
+        assert_eq!(error.code(), "UNTRUSTED_REGISTRY");
+    }
+
+    #[test]
+    fn test_check_id_commitment_span() {
+        let sent = b"POST / HTTP/1.1\r\n\r\n{\"ip\":\"1.2.3.4\",\"nuip\":\0\0\0}";
+        let start = sent.iter().position(|b| *b == 0).unwrap();
+
+        assert!(check_id_commitment_span(sent, start..start + 3, "nuip").is_ok());
+        // Partial value
+        assert!(check_id_commitment_span(sent, start..start + 2, "nuip").is_err());
+        // Commitment over another field
+        let ip = sent.windows(7).position(|w| w == b"1.2.3.4").unwrap();
+        assert!(check_id_commitment_span(sent, ip..ip + 7, "nuip").is_err());
+        // Commitment over the request head
+        assert!(check_id_commitment_span(sent, 0..4, "nuip").is_err());
+    }
+
+    #[test]
+    fn test_duplicate_id_field_is_rejected() {
+        // The registry reads the last "nuip"; the commitment covers the first
+        let sent = b"POST / HTTP/1.1\r\n\r\n{\"nuip\":\0\0\0,\"nuip\":454545454}";
+        let start = sent.iter().position(|b| *b == 0).unwrap();
+        let error = check_id_commitment_span(sent, start..start + 3, "nuip").unwrap_err();
+        assert_eq!(error.code(), "COMMITMENT_MISMATCH");
+        assert!(error.to_string().contains("2 'nuip' fields"));
+
+        // Escaped key spelling the same name
+        let sent = b"POST / HTTP/1.1\r\n\r\n{\"nuip\":\0\0\0,\"nu\\u0069p\":454545454}";
+        assert!(check_id_commitment_span(sent, start..start + 3, "nuip").is_err());
+
+        // Committed value nested in another object, literal value at the top level
+        let sent = b"POST / HTTP/1.1\r\n\r\n{\"x\":{\"nuip\":\0\0\0},\"nuip\":454545454}";
+        let start = sent.iter().position(|b| *b == 0).unwrap();
+        assert!(check_id_commitment_span(sent, start..start + 3, "nuip").is_err());
+    }
 }
@@ -0,0 +1,232 @@
+//! Local stand-ins shared by the integration tests
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use alloy::primitives::keccak256;
+use axum::{extract::State, routing::post, Json, Router};
+use mock_registry::{MockRegistry, MockRegistryConfig};
+use notary::tls::{spki_sha256, TlsTrust};
+use serde_json::{json, Value};
+
+/// Anvil's first default account - signs the verifier's transactions
+pub const VERIFIER_PRIVATE_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Chain id reported by the stand-in (anvil's default)
+pub const CHAIN_ID: u64 = 31337;
+
+const ONE_GWEI: &str = "0x3b9aca00";
+
+/// A JSON-RPC call received by [`RpcStandIn`]
+#[derive(Debug, Clone)]
+pub struct RpcCall {
+    pub method: String,
+    pub params: Value,
+}
+
+/// Shared state of the stand-in node
+#[derive(Default)]
+struct Node {
+    calls: Mutex<Vec<RpcCall>>,
+    /// `eth_call` results by function selector
+    call_results: Mutex<HashMap<[u8; 4], Vec<u8>>>,
+}
+
+/// Minimal Ethereum JSON-RPC node
+///
+/// Answers what alloy's recommended fillers, `eth_call`s and receipt polling ask for,
+/// records every call, and reports every raw transaction as mined successfully in block 1.
+/// `eth_call` returns empty data unless a result was set with [`RpcStandIn::answer_call`].
+pub struct RpcStandIn {
+    url: String,
+    node: Arc<Node>,
+}
+
+impl RpcStandIn {
+    pub async fn start() -> Self {
+        let node = Arc::new(Node::default());
+        let app = Router::new().route("/", post(handle)).with_state(node.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Self { url, node }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn calls(&self) -> Vec<RpcCall> {
+        self.node.calls.lock().unwrap().clone()
+    }
+
+    /// Answer `eth_call`s to the function with `selector` with the ABI-encoded `result`
+    pub fn answer_call(&self, selector: [u8; 4], result: Vec<u8>) {
+        self.node
+            .call_results
+            .lock()
+            .unwrap()
+            .insert(selector, result);
+    }
+
+    /// Signed transactions received through `eth_sendRawTransaction`
+    pub fn raw_transactions(&self) -> Vec<Vec<u8>> {
+        raw_transactions(&self.node)
+    }
+}
+
+fn raw_transactions(node: &Node) -> Vec<Vec<u8>> {
+    node.calls
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|call| call.method == "eth_sendRawTransaction")
+        .filter_map(|call| call.params[0].as_str())
+        .map(|raw| hex::decode(raw.trim_start_matches("0x")).unwrap())
+        .collect()
+}
+
+async fn handle(State(node): State<Arc<Node>>, Json(request): Json<Value>) -> Json<Value> {
+    match request {
+        Value::Array(batch) => Json(Value::Array(
+            batch.into_iter().map(|r| respond(&node, r)).collect(),
+        )),
+        single => Json(respond(&node, single)),
+    }
+}
+
+fn respond(node: &Node, request: Value) -> Value {
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    let params = request["params"].clone();
+    node.calls.lock().unwrap().push(RpcCall {
+        method: method.clone(),
+        params: params.clone(),
+    });
+
+    let result = match method.as_str() {
+        "eth_chainId" => json!(format!("0x{:x}", CHAIN_ID)),
+        "eth_blockNumber" => json!("0x1"),
+        "eth_getTransactionCount" => json!("0x0"),
+        "eth_estimateGas" => json!("0x1000000"),
+        "eth_call" => {
+            let tx = &params[0];
+            let input = tx["input"].as_str().or_else(|| tx["data"].as_str()).unwrap_or_default();
+            let input = hex::decode(input.trim_start_matches("0x")).unwrap_or_default();
+            let result = input
+                .get(..4)
+                .and_then(|selector| node.call_results.lock().unwrap().get(selector).cloned())
+                .unwrap_or_default();
+            json!(format!("0x{}", hex::encode(result)))
+        }
+        "eth_gasPrice" | "eth_maxPriorityFeePerGas" => json!(ONE_GWEI),
+        "eth_feeHistory" => json!({
+            "oldestBlock": "0x1",
+            "baseFeePerGas": [ONE_GWEI, ONE_GWEI],
+            "gasUsedRatio": [0.5],
+            "reward": [[ONE_GWEI]]
+        }),
+        "eth_getBlockByNumber" | "eth_getBlockByHash" => block(),
+        "eth_newBlockFilter" => json!("0x1"),
+        "eth_getFilterChanges" => json!([]),
+        "eth_uninstallFilter" => json!(true),
+        "eth_sendRawTransaction" => {
+            let raw = params[0].as_str().unwrap_or_default();
+            let bytes = hex::decode(raw.trim_start_matches("0x")).unwrap_or_default();
+            json!(format!("0x{}", hex::encode(keccak256(bytes))))
+        }
+        "eth_getTransactionReceipt" => {
+            let hash = params[0].as_str().unwrap_or_default().to_lowercase();
+            raw_transactions(node)
+                .into_iter()
+                .find(|raw| format!("0x{}", hex::encode(keccak256(raw))) == hash)
+                .map(|_| receipt(&hash))
+                .unwrap_or(Value::Null)
+        }
+        _ => {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32601, "message": format!("method {} not supported", method) }
+            })
+        }
+    };
+
+    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+}
+
+fn zero_hash() -> String {
+    format!("0x{}", "00".repeat(32))
+}
+
+fn block() -> Value {
+    json!({
+        "number": "0x1",
+        "hash": format!("0x{}", "11".repeat(32)),
+        "parentHash": zero_hash(),
+        "sha3Uncles": zero_hash(),
+        "miner": format!("0x{}", "00".repeat(20)),
+        "stateRoot": zero_hash(),
+        "transactionsRoot": zero_hash(),
+        "receiptsRoot": zero_hash(),
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "difficulty": "0x0",
+        "gasLimit": "0x1c9c380",
+        "gasUsed": "0x0",
+        "timestamp": "0x0",
+        "extraData": "0x",
+        "mixHash": zero_hash(),
+        "nonce": "0x0000000000000000",
+        "baseFeePerGas": ONE_GWEI,
+        "size": "0x0",
+        "uncles": [],
+        "transactions": []
+    })
+}
+
+fn receipt(hash: &str) -> Value {
+    json!({
+        "transactionHash": hash,
+        "transactionIndex": "0x0",
+        "blockHash": format!("0x{}", "11".repeat(32)),
+        "blockNumber": "0x1",
+        "from": format!("0x{}", "00".repeat(20)),
+        "to": format!("0x{}", "00".repeat(20)),
+        "cumulativeGasUsed": "0x5208",
+        "gasUsed": "0x5208",
+        "effectiveGasPrice": ONE_GWEI,
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "type": "0x2",
+        "status": "0x1"
+    })
+}
+
+/// Mock registry plus the TLS trust (CA bundle and SPKI pin) needed to reach it
+pub struct LocalRegistry {
+    pub registry: MockRegistry,
+    pub tls: TlsTrust,
+}
+
+pub async fn start_registry(config: MockRegistryConfig) -> LocalRegistry {
+    let registry = MockRegistry::start(config).await.unwrap();
+
+    let ca_path = std::env::temp_dir().join(format!(
+        "proofheir-registry-{}-{}.pem",
+        std::process::id(),
+        registry.addr().port()
+    ));
+    std::fs::write(&ca_path, registry.ca_pem()).unwrap();
+
+    let tls = TlsTrust {
+        ca_bundles: vec![ca_path],
+        native_roots: false,
+        spki_pins: vec![hex::encode(spki_sha256(registry.cert_der()).unwrap())],
+    };
+
+    LocalRegistry { registry, tls }
+}
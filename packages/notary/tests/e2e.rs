@@ -0,0 +1,267 @@
+//! End-to-end tests for `generate_death_proof` against local stand-ins
+//!
+//! The full flow (MPC-TLS + UltraHonk proving) takes minutes and must run in release mode:
+//!
+//! ```bash
+//! cargo test --release -p notary --test e2e -- --ignored
+//! ```
+//!
+//! Loading the embedded circuit is not ignored. It reads the SRS from `SRS_PATH` when set
+//! (written by `notary srs --out`), so it also runs offline; otherwise it downloads the points.
+mod common;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::primitives::B256;
+use alloy::sol;
+use alloy::sol_types::{SolCall, SolValue};
+use common::{start_registry, LocalRegistry, RpcStandIn, VERIFIER_PRIVATE_KEY};
+use k256::sha2::{Digest, Sha256};
+use mock_registry::{Fault, MockRegistryConfig, ALIVE, DECEASED};
+use notary::chain::{AlloyChain, ChainClient, MockChain};
+use notary::circuit::{write_srs, PROGRAM_JSON};
+use notary::commitment::id_commitment;
+use notary::proof_gen::generate_death_proof_with_config;
+use notary::proof_layout::FIELD_SIZE;
+use notary::types::PUBLIC_INPUT_FIELDS;
+use notary::{
+    CircuitContext, ColombiaRegistry, NotaryError, Nuip, ProofGenConfig, ProofHeirError, ProofStage, Progress,
+    ProgressEvent, ProvingPool, PublicInputs, RegistryDomain, Submission, SubmissionMode,
+};
+
+sol! {
+    function proveDeathAndRegisterHeir(bytes calldata proof, bytes32[] calldata publicInputs) external;
+    function getIdentityCommitment() external view returns (bytes32);
+    function trustedServerHash() external view returns (bytes32);
+}
+
+const DECEASED_NUIP: u64 = 454545454;
+const ALIVE_NUIP: u64 = 123456789;
+const RECIPIENT: [u8; 20] = [0xab; 20];
+const TESTATOR: [u8; 20] = [0xcd; 20];
+const SALT: [u8; 32] = [0x11; 32];
+
+fn nuip_of(nuip: u64) -> Nuip {
+    Nuip::new(nuip.to_string()).unwrap()
+}
+
+fn server_hash(domain: &str) -> [u8; 32] {
+    RegistryDomain::new(domain).unwrap().server_hash()
+}
+
+async fn local_registry() -> LocalRegistry {
+    start_registry(
+        MockRegistryConfig::default()
+            .with_record(DECEASED_NUIP, DECEASED)
+            .with_record(ALIVE_NUIP, ALIVE),
+    )
+    .await
+}
+
+fn config(local: &LocalRegistry, chain: Arc<dyn ChainClient>) -> ProofGenConfig {
+    ProofGenConfig {
+        registry: Arc::new(ColombiaRegistry::new(local.registry.url())),
+        notary_addr: None,
+        tls: local.tls.clone(),
+        submission: SubmissionMode::Submit,
+        chain,
+        preflight: true,
+        progress: Progress::default(),
+        proving: ProvingPool::global().clone(),
+    }
+}
+
+async fn prove_on(
+    local: &LocalRegistry,
+    chain: Arc<dyn ChainClient>,
+    nuip: u64,
+) -> Result<notary::ProofGenerationResult, NotaryError> {
+    generate_death_proof_with_config(
+        RECIPIENT.into(),
+        nuip_of(nuip),
+        SALT.into(),
+        TESTATOR.into(),
+        &config(local, chain),
+    )
+    .await
+}
+
+async fn prove(local: &LocalRegistry, nuip: u64) -> Result<notary::ProofGenerationResult, NotaryError> {
+    prove_on(local, Arc::new(MockChain::default()), nuip).await
+}
+
+/// The embedded `circuits.json` loads the way the API and `notary_server` load it at startup:
+/// SRS from a file, then the public-input layout and proof size checked against the VK
+#[test]
+fn test_embedded_circuit_loads() {
+    let (srs_path, downloaded) = match std::env::var("SRS_PATH") {
+        Ok(path) if !path.trim().is_empty() => (PathBuf::from(path), false),
+        _ => {
+            let path = std::env::temp_dir().join(format!("proofheir-e2e-{}.srs", std::process::id()));
+            write_srs(PROGRAM_JSON, &path).expect("SRS download failed");
+            (path, true)
+        }
+    };
+
+    let circuit = CircuitContext::load(PROGRAM_JSON, Some(&srs_path));
+    if downloaded {
+        std::fs::remove_file(&srs_path).unwrap();
+    }
+
+    let circuit = circuit.expect("embedded circuits.json must load");
+    assert_eq!(circuit.layout().public_input_fields(), PUBLIC_INPUT_FIELDS);
+    assert_eq!(
+        circuit.vk_hash(),
+        <[u8; 32]>::from(Sha256::digest(circuit.vk()))
+    );
+}
+
+#[tokio::test]
+async fn test_pre_check_rejects_alive_subject() {
+    let local = local_registry().await;
+    assert!(matches!(prove(&local, ALIVE_NUIP).await, Err(NotaryError::SubjectAlive)));
+}
+
+#[tokio::test]
+async fn test_pre_check_surfaces_registry_faults() {
+    let local = local_registry().await;
+
+    for fault in [Fault::NotFound, Fault::ServerError, Fault::MalformedJson] {
+        local.registry.set_fault(fault);
+        let result = prove(&local, DECEASED_NUIP).await;
+        assert!(
+            matches!(result, Err(NotaryError::RegistryUnreachable(_))),
+            "{:?} should surface as REGISTRY_UNREACHABLE",
+            fault
+        );
+    }
+
+    // Unknown NUIP: the registry answers 404
+    local.registry.set_fault(Fault::None);
+    assert!(matches!(prove(&local, 1).await, Err(NotaryError::RegistryUnreachable(_))));
+}
+
+#[tokio::test]
+async fn test_preflight_fails_before_mpc_tls() {
+    let local = local_registry().await;
+
+    // Nothing registered on-chain: rejected right after the registry pre-check
+    let result = prove(&local, DECEASED_NUIP).await;
+    assert!(matches!(
+        result,
+        Err(NotaryError::Preflight(ProofHeirError::IdentityNotRegistered))
+    ));
+
+    // Registered, but the contract trusts another registry
+    let chain = Arc::new(MockChain::default());
+    chain.register_identity(
+        TESTATOR.into(),
+        id_commitment(&nuip_of(DECEASED_NUIP), SALT.into()).into(),
+    );
+    chain.set_trusted_server_hash(server_hash("registraduria.gov.co").into());
+    let result = prove_on(&local, chain.clone(), DECEASED_NUIP).await;
+    assert!(matches!(
+        result,
+        Err(NotaryError::Preflight(ProofHeirError::InvalidDataSource { .. }))
+    ));
+    assert!(chain.simulations().is_empty());
+}
+
+#[tokio::test]
+#[ignore = "runs MPC-TLS and UltraHonk proving; use --release -- --ignored"]
+async fn test_generate_death_proof_end_to_end() {
+    let local = local_registry().await;
+    let rpc = RpcStandIn::start().await;
+
+    // Testator registered on-chain, contract trusting the local registry
+    rpc.answer_call(
+        getIdentityCommitmentCall::SELECTOR,
+        B256::from(id_commitment(&nuip_of(DECEASED_NUIP), SALT.into())).abi_encode(),
+    );
+    rpc.answer_call(
+        trustedServerHashCall::SELECTOR,
+        B256::from(server_hash("localhost")).abi_encode(),
+    );
+    let chain = Arc::new(AlloyChain::new(rpc.url(), Some(VERIFIER_PRIVATE_KEY.to_string())));
+
+    let completed = Arc::new(Mutex::new(Vec::new()));
+    let sink = completed.clone();
+    let config = ProofGenConfig {
+        progress: Progress::new(move |event| {
+            if let ProgressEvent::Completed { stage, .. } = event {
+                sink.lock().unwrap().push(stage);
+            }
+        }),
+        ..config(&local, chain)
+    };
+
+    // Pre-check, pre-flight, MPC-TLS, commitments, Noir proof, simulation and on-chain submission
+    let result = generate_death_proof_with_config(
+        RECIPIENT.into(),
+        nuip_of(DECEASED_NUIP),
+        SALT.into(),
+        TESTATOR.into(),
+        &config,
+    )
+    .await
+    .expect("proof generation failed");
+
+    // Every stage was reported, in order
+    assert_eq!(
+        *completed.lock().unwrap(),
+        [
+            ProofStage::PreVerification,
+            ProofStage::Preflight,
+            ProofStage::MpcTlsSetup,
+            ProofStage::HttpRequest,
+            ProofStage::Commitment,
+            ProofStage::SrsSetup,
+            ProofStage::Proving,
+            ProofStage::Verification,
+            ProofStage::Transaction,
+        ]
+    );
+
+    // Public inputs serialized for Solidity: one byte per field
+    assert_eq!(result.public_inputs.len(), PUBLIC_INPUT_FIELDS);
+    let fields: Vec<u8> = result
+        .public_inputs
+        .iter()
+        .flat_map(|field| hex::decode(field.trim_start_matches("0x")).unwrap())
+        .collect();
+    let inputs = PublicInputs::from_proof_fields(&fields).unwrap();
+
+    let mut nuip_padded = DECEASED_NUIP.to_string().into_bytes();
+    nuip_padded.resize(15, 0);
+    let id_commitment: [u8; 32] = Sha256::digest([nuip_padded.as_slice(), &SALT].concat()).into();
+
+    assert_eq!(inputs.recipient, RECIPIENT);
+    assert_eq!(inputs.server_hash, server_hash("localhost"));
+    assert_eq!(inputs.id_commitment, id_commitment);
+
+    // The proof embeds the same public inputs
+    assert_eq!(&result.proof[..PUBLIC_INPUT_FIELDS * FIELD_SIZE], fields.as_slice());
+
+    assert!(matches!(result.submission, Submission::Submitted(_)));
+
+    // Exactly one transaction, to the testator's delegated account, with the proof body as calldata
+    let raw_transactions = rpc.raw_transactions();
+    assert_eq!(raw_transactions.len(), 1);
+    let tx = TxEnvelope::decode_2718(&mut raw_transactions[0].as_slice()).unwrap();
+    assert_eq!(tx.to().unwrap().as_slice(), TESTATOR.as_slice());
+
+    let call = proveDeathAndRegisterHeirCall::abi_decode(tx.input(), true).unwrap();
+    assert_eq!(
+        call.proof.as_ref(),
+        &result.proof[PUBLIC_INPUT_FIELDS * FIELD_SIZE..]
+    );
+    let submitted: Vec<u8> = call
+        .publicInputs
+        .iter()
+        .flat_map(|field| field.as_slice().to_vec())
+        .collect();
+    assert_eq!(submitted, fields);
+}
@@ -0,0 +1,42 @@
+#!/bin/bash
+# Rebuild every artifact derived from packages/circuits/src/main.nr:
+#   - packages/notary/circuits.json          (embedded by the notary and the API)
+#   - packages/contracts/src/Verifier.sol    (HonkVerifier used by ProofHeir)
+#   - packages/contracts/test/fixtures/proof (proof for Prover.toml, used by VerifierIntegration.t.sol)
+#
+# Requires nargo 1.0.0-beta.8 and bb 1.0.0-nightly.20250723 (see packages/circuits/README.md)
+
+set -e
+
+# Public input fields the notary serializes (PUBLIC_INPUT_FIELDS) plus the 16 pairing point fields
+EXPECTED_PUBLIC_INPUTS=164
+
+cd packages/circuits
+
+echo "🔧 Compiling circuit..."
+nargo compile
+nargo test
+
+echo "🧮 Executing with Prover.toml..."
+nargo execute
+
+echo "🔑 Writing verification key and proof (keccak oracle)..."
+rm -f ./target/vk ./target/proof ./target/public_inputs
+bb write_vk -b ./target/circuits.json -o ./target --oracle_hash keccak
+bb prove --bytecode_path ./target/circuits.json --witness_path ./target/circuits.gz -o ./target --oracle_hash keccak
+bb verify -k ./target/vk -p ./target/proof --oracle_hash keccak
+
+echo "📜 Writing Solidity verifier..."
+bb write_solidity_verifier -k ./target/vk -o ./target/Verifier.sol
+
+if ! grep -q "NUMBER_OF_PUBLIC_INPUTS = ${EXPECTED_PUBLIC_INPUTS}" ./target/Verifier.sol; then
+  echo "❌ Error: Verifier.sol does not expect ${EXPECTED_PUBLIC_INPUTS} public inputs"
+  echo "Update PUBLIC_INPUT_FIELDS (notary) and PUBLIC_INPUTS_LENGTH (ProofHeir.sol) with the circuit"
+  exit 1
+fi
+
+cp ./target/circuits.json ../notary/circuits.json
+cp ./target/Verifier.sol ../contracts/src/Verifier.sol
+cp ./target/proof ../contracts/test/fixtures/proof
+
+echo "✅ Circuit artifacts updated. Run 'cargo test -p notary' and 'forge test' before committing."